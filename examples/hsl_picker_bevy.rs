@@ -17,7 +17,7 @@ fn main() {
             }),
             ..default()
         }))
-        .add_plugins(UiInspectorPlugin)
+        .add_plugins(UiInspectorPlugin::default())
         .add_systems(Startup, setup)
         .run();
 }
@@ -0,0 +1,147 @@
+use bevy::{prelude::*, sprite::BorderRect};
+use bevy_egui::egui;
+
+use crate::{format_px, paint_label};
+
+pub(crate) const MARGIN_COLOR: Color = Color::srgba(0.96, 0.7, 0.42, 0.66);
+pub(crate) const BORDER_COLOR: Color = Color::srgba(1.0, 0.9, 0.6, 0.66);
+pub(crate) const PADDING_COLOR: Color = Color::srgba(0.58, 0.77, 0.49, 0.55);
+pub(crate) const CONTENT_COLOR: Color = Color::srgba(0.44, 0.66, 0.86, 0.66);
+
+// The areas of a node like the devtools box model, in UI pixels.
+pub(crate) struct BoxModel {
+    pub border_box: Rect,
+    pub margin: BorderRect,
+    pub border: BorderRect,
+    pub padding: BorderRect,
+}
+
+impl BoxModel {
+    pub fn new(
+        node: &Node,
+        computed: &ComputedNode,
+        transform: &GlobalTransform,
+        parent: Option<&ComputedNode>,
+        viewport_size: Vec2,
+    ) -> Self {
+        let inverse = computed.inverse_scale_factor();
+        let to_ui = |rect: BorderRect| BorderRect {
+            left: rect.left * inverse,
+            right: rect.right * inverse,
+            top: rect.top * inverse,
+            bottom: rect.bottom * inverse,
+        };
+        // Margins aren't part of `ComputedNode`, percentages resolve against the parent's
+        // content width and `auto` margins are shown as zero.
+        let parent_width = parent
+            .map(|parent| {
+                let inset = parent.content_inset();
+                (parent.size().x - inset.left - inset.right) * parent.inverse_scale_factor()
+            })
+            .unwrap_or(viewport_size.x);
+        let resolve = |val: Val| val.resolve(parent_width, viewport_size).unwrap_or(0.0);
+
+        Self {
+            border_box: Rect::from_center_size(
+                transform.translation().truncate() * inverse,
+                computed.size() * inverse,
+            ),
+            margin: BorderRect {
+                left: resolve(node.margin.left),
+                right: resolve(node.margin.right),
+                top: resolve(node.margin.top),
+                bottom: resolve(node.margin.bottom),
+            },
+            border: to_ui(computed.border()),
+            padding: to_ui(computed.padding()),
+        }
+    }
+
+    pub fn margin_box(&self) -> Rect {
+        outset(self.border_box, &self.margin)
+    }
+
+    pub fn padding_box(&self) -> Rect {
+        inset(self.border_box, &self.border)
+    }
+
+    pub fn content_box(&self) -> Rect {
+        inset(self.padding_box(), &self.padding)
+    }
+}
+
+fn inset(rect: Rect, sides: &BorderRect) -> Rect {
+    Rect {
+        min: rect.min + Vec2::new(sides.left, sides.top),
+        max: rect.max - Vec2::new(sides.right, sides.bottom),
+    }
+}
+
+fn outset(rect: Rect, sides: &BorderRect) -> Rect {
+    Rect {
+        min: rect.min - Vec2::new(sides.left, sides.top),
+        max: rect.max + Vec2::new(sides.right, sides.bottom),
+    }
+}
+
+fn to_egui(color: Color) -> egui::Color32 {
+    let [r, g, b, _] = color.to_srgba().to_u8_array();
+    egui::Color32::from_rgb(r, g, b)
+}
+
+// Labels every non-zero side of each area in the middle of its band, and the content size in
+// the middle of the content box.
+pub(crate) fn paint_box_model_labels(ctx: &egui::Context, box_model: &BoxModel, ui_scale: f32) {
+    let bands = [
+        (box_model.margin_box(), &box_model.margin, MARGIN_COLOR),
+        (box_model.border_box, &box_model.border, BORDER_COLOR),
+        (box_model.padding_box(), &box_model.padding, PADDING_COLOR),
+    ];
+    for (outer, sides, color) in bands {
+        let center = outer.center();
+        let labels = [
+            (
+                sides.left,
+                Vec2::new(outer.min.x + sides.left / 2.0, center.y),
+            ),
+            (
+                sides.right,
+                Vec2::new(outer.max.x - sides.right / 2.0, center.y),
+            ),
+            (
+                sides.top,
+                Vec2::new(center.x, outer.min.y + sides.top / 2.0),
+            ),
+            (
+                sides.bottom,
+                Vec2::new(center.x, outer.max.y - sides.bottom / 2.0),
+            ),
+        ];
+        for (value, pos) in labels {
+            if value > 0.0 {
+                let pos = pos * ui_scale;
+                paint_label(
+                    ctx,
+                    "ui_inspector_box_model",
+                    egui::pos2(pos.x, pos.y),
+                    format_px(value),
+                    to_egui(color),
+                );
+            }
+        }
+    }
+
+    let content = box_model.content_box();
+    let center = content.center() * ui_scale;
+    paint_label(
+        ctx,
+        "ui_inspector_box_model",
+        egui::pos2(center.x, center.y),
+        format!(
+            "{} × {}",
+            format_px(content.width()),
+            format_px(content.height())
+        ),
+        to_egui(CONTENT_COLOR),
+    );
+}
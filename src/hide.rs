@@ -0,0 +1,231 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::ignore::IgnoredNodes;
+
+/// How the eye icon of a tree row hides its node in the app.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum HideMode {
+    /// `Visibility::Hidden`, the node keeps its place in the layout.
+    #[default]
+    Visibility,
+    /// `Display::None`, the node is taken out of the layout.
+    Display,
+}
+
+// The value a node had before the inspector hid it.
+enum SavedValue {
+    Visibility(Visibility),
+    Display(Display),
+}
+
+#[derive(Resource, Default)]
+pub(crate) struct HiddenNodes {
+    pub mode: HideMode,
+    // Nodes hidden with their eye icon.
+    saved: HashMap<Entity, SavedValue>,
+    // Nodes outside the isolated subtree. Their value is kept in `saved` instead when their
+    // eye icon hid them first, so each value is only ever saved once.
+    isolation: HashMap<Entity, Option<SavedValue>>,
+    pub isolated: Option<Entity>,
+}
+
+impl HiddenNodes {
+    pub fn new(mode: HideMode) -> Self {
+        Self { mode, ..default() }
+    }
+
+    // Whether the node is hidden by its eye icon or by isolation.
+    pub fn is_hidden(&self, entity: Entity) -> bool {
+        self.saved.contains_key(&entity) || self.isolation.contains_key(&entity)
+    }
+}
+
+// Hides the node, or shows it again when its eye icon or isolation hid it, giving it back the
+// value it had before.
+pub(crate) fn toggle_hidden(world: &mut World, entity: Entity) {
+    let mut hidden = world.resource_mut::<HiddenNodes>();
+    let saved = hidden.saved.remove(&entity);
+    let isolated = hidden.isolation.remove(&entity).flatten();
+    // At most one of them holds the value.
+    if let Some(value) = saved.or(isolated) {
+        restore(world, entity, value);
+        return;
+    }
+    let mode = hidden.mode;
+    if let Some(value) = hide(world, entity, mode) {
+        world
+            .resource_mut::<HiddenNodes>()
+            .saved
+            .insert(entity, value);
+    }
+}
+
+// Hides everything but the subtree of `entity` and its ancestors, or shows it all again with
+// `None`. Isolation always hides with `Visibility`, so the subtree's layout doesn't change.
+pub(crate) fn isolate(world: &mut World, entity: Option<Entity>) {
+    let previous = {
+        let mut hidden = world.resource_mut::<HiddenNodes>();
+        hidden.isolated = entity;
+        std::mem::take(&mut hidden.isolation)
+    };
+    for (entity, value) in previous {
+        if let Some(value) = value {
+            restore(world, entity, value);
+        }
+    }
+    let Some(entity) = entity.filter(|entity| world.get_entity(*entity).is_ok()) else {
+        return;
+    };
+
+    // Hiding the siblings of every node on the way up hides everything else.
+    let mut others = Vec::new();
+    let mut current = entity;
+    while let Some(parent) = world.get::<Parent>(current).map(Parent::get) {
+        if let Some(children) = world.get::<Children>(parent) {
+            others.extend(children.iter().filter(|child| **child != current));
+        }
+        current = parent;
+    }
+    let mut roots = world.query_filtered::<Entity, (With<Node>, Without<Parent>)>();
+    others.extend(roots.iter(world).filter(|root| *root != current));
+
+    let mut isolation = HashMap::new();
+    for other in others {
+        if world.resource::<IgnoredNodes>().is_ignored(other) {
+            continue;
+        }
+        if world.resource::<HiddenNodes>().saved.contains_key(&other) {
+            isolation.insert(other, None);
+        } else if let Some(value) = hide(world, other, HideMode::Visibility) {
+            isolation.insert(other, Some(value));
+        }
+    }
+    world.resource_mut::<HiddenNodes>().isolation = isolation;
+}
+
+fn hide(world: &mut World, entity: Entity, mode: HideMode) -> Option<SavedValue> {
+    match mode {
+        HideMode::Visibility => {
+            let mut visibility = world.get_mut::<Visibility>(entity)?;
+            let value = SavedValue::Visibility(*visibility);
+            *visibility = Visibility::Hidden;
+            Some(value)
+        }
+        HideMode::Display => {
+            let mut node = world.get_mut::<Node>(entity)?;
+            let value = SavedValue::Display(node.display);
+            node.display = Display::None;
+            Some(value)
+        }
+    }
+}
+
+fn restore(world: &mut World, entity: Entity, value: SavedValue) {
+    match value {
+        SavedValue::Visibility(value) => {
+            if let Some(mut visibility) = world.get_mut::<Visibility>(entity) {
+                *visibility = value;
+            }
+        }
+        SavedValue::Display(value) => {
+            if let Some(mut node) = world.get_mut::<Node>(entity) {
+                node.display = value;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ignore::IgnoredNodes;
+
+    // A root with three children, each with a different value to restore.
+    fn world(mode: HideMode) -> (World, Entity, [Entity; 3]) {
+        let mut world = World::new();
+        world.insert_resource(HiddenNodes::new(mode));
+        world.init_resource::<IgnoredNodes>();
+        let children = [
+            world.spawn((Node::default(), Visibility::Visible)).id(),
+            world
+                .spawn((
+                    Node {
+                        display: Display::Grid,
+                        ..default()
+                    },
+                    Visibility::Inherited,
+                ))
+                .id(),
+            world.spawn((Node::default(), Visibility::Visible)).id(),
+        ];
+        let root = world.spawn((Node::default(), Visibility::Visible)).id();
+        world.entity_mut(root).add_children(&children);
+        (world, root, children)
+    }
+
+    fn visibility(world: &World, entity: Entity) -> Visibility {
+        *world.get::<Visibility>(entity).unwrap()
+    }
+
+    fn display(world: &World, entity: Entity) -> Display {
+        world.get::<Node>(entity).unwrap().display
+    }
+
+    #[test]
+    fn eye_hidden_node_stays_hidden_after_isolation() {
+        let (mut world, _, [kept, hidden, _]) = world(HideMode::Visibility);
+        toggle_hidden(&mut world, hidden);
+        isolate(&mut world, Some(kept));
+        assert_eq!(visibility(&world, hidden), Visibility::Hidden);
+        isolate(&mut world, None);
+        assert_eq!(visibility(&world, hidden), Visibility::Hidden);
+        assert!(world.resource::<HiddenNodes>().is_hidden(hidden));
+
+        toggle_hidden(&mut world, hidden);
+        assert_eq!(visibility(&world, hidden), Visibility::Inherited);
+        assert!(!world.resource::<HiddenNodes>().is_hidden(hidden));
+    }
+
+    #[test]
+    fn eye_shows_a_node_hidden_by_isolation() {
+        let (mut world, root, [kept, shown, other]) = world(HideMode::Visibility);
+        isolate(&mut world, Some(kept));
+        assert!(world.resource::<HiddenNodes>().is_hidden(shown));
+        assert!(!world.resource::<HiddenNodes>().is_hidden(root));
+
+        toggle_hidden(&mut world, shown);
+        assert_eq!(visibility(&world, shown), Visibility::Inherited);
+        assert!(!world.resource::<HiddenNodes>().is_hidden(shown));
+        // Hidden again with its eye, it stays hidden once isolation stops.
+        toggle_hidden(&mut world, other);
+        toggle_hidden(&mut world, other);
+        isolate(&mut world, None);
+        assert_eq!(visibility(&world, shown), Visibility::Inherited);
+        assert_eq!(visibility(&world, other), Visibility::Hidden);
+
+        toggle_hidden(&mut world, other);
+        assert_eq!(visibility(&world, other), Visibility::Visible);
+        assert_eq!(visibility(&world, kept), Visibility::Visible);
+    }
+
+    #[test]
+    fn display_mode_and_isolation_restore_their_own_values() {
+        let (mut world, _, [kept, hidden, other]) = world(HideMode::Display);
+        toggle_hidden(&mut world, hidden);
+        assert_eq!(display(&world, hidden), Display::None);
+        assert_eq!(visibility(&world, hidden), Visibility::Inherited);
+
+        // Isolation hides with `Visibility`, but leaves nodes hidden by their eye alone.
+        isolate(&mut world, Some(kept));
+        assert_eq!(visibility(&world, hidden), Visibility::Inherited);
+        assert_eq!(visibility(&world, other), Visibility::Hidden);
+        assert_eq!(display(&world, other), Display::Flex);
+        isolate(&mut world, None);
+        assert_eq!(visibility(&world, other), Visibility::Visible);
+        assert_eq!(display(&world, hidden), Display::None);
+
+        toggle_hidden(&mut world, hidden);
+        assert_eq!(display(&world, hidden), Display::Grid);
+        assert_eq!(visibility(&world, hidden), Visibility::Inherited);
+    }
+}
@@ -0,0 +1,467 @@
+use std::any::TypeId;
+
+use bevy::{
+    ecs::reflect::ReflectComponent,
+    prelude::*,
+    reflect::{PartialReflect, TypeRegistry},
+};
+use bevy_egui::egui;
+
+use crate::{hide, ActiveStyleInspection, RestorePreviousResource};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum DropPosition {
+    Before,
+    After,
+    Inside,
+}
+
+// Changes to the app's hierarchy made from the tree, applied once the inspector is drawn.
+pub(crate) enum TreeEdit {
+    Move {
+        entity: Entity,
+        target: Entity,
+        position: DropPosition,
+    },
+    Despawn(Entity),
+    Duplicate(Entity),
+    AddChildNode(Entity),
+    AddChildText(Entity),
+    Wrap(Entity),
+    Unwrap(Entity),
+    ToggleHidden(Entity),
+    // Hides everything outside the node's subtree, `None` shows it again.
+    Isolate(Option<Entity>),
+    // An empty name removes the `Name` component.
+    Rename {
+        entity: Entity,
+        name: String,
+    },
+}
+
+#[derive(Resource, Default)]
+pub(crate) struct TreeEdits {
+    // Row being dragged, and the row and side it would be dropped on this frame.
+    pub dragged: Option<Entity>,
+    pub drop_target: Option<(Entity, DropPosition)>,
+    pub pending: Vec<TreeEdit>,
+    // Row whose name is being edited, with the name typed so far.
+    pub renaming: Option<(Entity, String)>,
+}
+
+// Lets a tree row be dragged onto another one, showing where it would land.
+pub(crate) fn drag_and_drop_row(
+    ui: &mut egui::Ui,
+    entity: Entity,
+    rect: egui::Rect,
+    edits: &mut TreeEdits,
+) {
+    let response = ui.interact(
+        rect,
+        egui::Id::new(("ui_inspector_tree_drag", entity)),
+        egui::Sense::drag(),
+    );
+    if response.drag_started() {
+        edits.dragged = Some(entity);
+    }
+    if edits.dragged.is_none_or(|dragged| dragged == entity) {
+        return;
+    }
+    let Some(pointer) = ui
+        .ctx()
+        .pointer_interact_pos()
+        .filter(|p| rect.contains(*p))
+    else {
+        return;
+    };
+    // The outer quarters of a row insert next to it, the middle moves into it.
+    let fraction = (pointer.y - rect.top()) / rect.height();
+    let position = if fraction < 0.25 {
+        DropPosition::Before
+    } else if fraction > 0.75 {
+        DropPosition::After
+    } else {
+        DropPosition::Inside
+    };
+    edits.drop_target = Some((entity, position));
+
+    let stroke = egui::Stroke::new(2.0, egui::Color32::from_rgb(100, 149, 237));
+    let painter = ui.painter();
+    match position {
+        DropPosition::Before => painter.hline(rect.x_range(), rect.top(), stroke),
+        DropPosition::After => painter.hline(rect.x_range(), rect.bottom(), stroke),
+        DropPosition::Inside => painter.rect_stroke(rect, 2.0, stroke),
+    };
+}
+
+// Right click menu of a tree row.
+pub(crate) fn row_context_menu(response: &egui::Response, entity: Entity, edits: &mut TreeEdits) {
+    response.context_menu(|ui| {
+        for (label, edit) in [
+            ("Add child node", TreeEdit::AddChildNode(entity)),
+            ("Add child text", TreeEdit::AddChildText(entity)),
+            ("Wrap in node", TreeEdit::Wrap(entity)),
+            ("Unwrap", TreeEdit::Unwrap(entity)),
+            ("Duplicate", TreeEdit::Duplicate(entity)),
+            ("Toggle visibility", TreeEdit::ToggleHidden(entity)),
+            ("Isolate", TreeEdit::Isolate(Some(entity))),
+            ("Despawn", TreeEdit::Despawn(entity)),
+        ] {
+            if ui.button(label).clicked() {
+                edits.pending.push(edit);
+                ui.close_menu();
+            }
+        }
+    });
+}
+
+// Text box replacing the label of a row being renamed. Enter or clicking elsewhere keeps the
+// name, Escape throws it away.
+pub(crate) fn rename_row(ui: &mut egui::Ui, edits: &mut TreeEdits) {
+    let Some((entity, name)) = edits.renaming.as_mut() else {
+        return;
+    };
+    let entity = *entity;
+    let response = ui.add(
+        egui::TextEdit::singleline(name)
+            .id(egui::Id::new(("ui_inspector_rename", entity)))
+            .hint_text("name")
+            .desired_width(f32::INFINITY),
+    );
+    if response.lost_focus() {
+        let cancelled = ui.input(|i| i.key_pressed(egui::Key::Escape));
+        if let Some((_, name)) = edits.renaming.take().filter(|_| !cancelled) {
+            edits.pending.push(TreeEdit::Rename { entity, name });
+        }
+    } else if !response.has_focus() {
+        response.request_focus();
+    }
+}
+
+// Called after every row is drawn, so the drop target of this frame is known.
+pub(crate) fn finish_drag(ctx: &egui::Context, edits: &mut TreeEdits) {
+    let Some(dragged) = edits.dragged else {
+        return;
+    };
+    if ctx.input(|i| i.pointer.any_down()) {
+        ctx.set_cursor_icon(egui::CursorIcon::Grabbing);
+        return;
+    }
+    if let Some((target, position)) = edits.drop_target.take() {
+        edits.pending.push(TreeEdit::Move {
+            entity: dragged,
+            target,
+            position,
+        });
+    }
+    edits.dragged = None;
+}
+
+pub(crate) fn apply_tree_edits(world: &mut World) {
+    let edits = std::mem::take(&mut world.resource_mut::<TreeEdits>().pending);
+    for edit in edits {
+        match edit {
+            TreeEdit::Move {
+                entity,
+                target,
+                position,
+            } => move_node(world, entity, target, position),
+            TreeEdit::Despawn(entity) => {
+                if let Ok(entity) = world.get_entity_mut(entity) {
+                    entity.despawn_recursive();
+                }
+            }
+            TreeEdit::Duplicate(entity) => {
+                if world.get_entity(entity).is_ok() {
+                    let copy = duplicate(world, entity);
+                    insert_next_to(world, entity, copy, 1);
+                    select(world, copy);
+                }
+            }
+            TreeEdit::AddChildNode(entity) => {
+                let child = world.spawn(Node::default()).id();
+                add_child(world, entity, child);
+            }
+            TreeEdit::AddChildText(entity) => {
+                let child = world.spawn(Text::new("Text")).id();
+                add_child(world, entity, child);
+            }
+            TreeEdit::Wrap(entity) => {
+                if world.get_entity(entity).is_ok() {
+                    let wrapper = world.spawn(Node::default()).id();
+                    insert_next_to(world, entity, wrapper, 0);
+                    // A wrapped root leaves the wrapper as the root, which has to keep drawing
+                    // to the same camera.
+                    if world.get::<Parent>(entity).is_none() {
+                        if let Some(target_camera) = world.get::<TargetCamera>(entity).cloned() {
+                            world.entity_mut(wrapper).insert(target_camera);
+                        }
+                    }
+                    world.entity_mut(wrapper).add_child(entity);
+                    select(world, wrapper);
+                }
+            }
+            TreeEdit::Unwrap(entity) => unwrap(world, entity),
+            TreeEdit::ToggleHidden(entity) => hide::toggle_hidden(world, entity),
+            TreeEdit::Isolate(entity) => hide::isolate(world, entity),
+            TreeEdit::Rename { entity, name } => {
+                if let Ok(mut entity) = world.get_entity_mut(entity) {
+                    let name = name.trim();
+                    if name.is_empty() {
+                        entity.remove::<Name>();
+                    } else {
+                        entity.insert(Name::new(name.to_string()));
+                    }
+                }
+            }
+        }
+    }
+    // Edits can despawn the selected node, which mustn't be inspected anymore.
+    let selected = world.resource::<RestorePreviousResource>().selected;
+    if selected.is_some_and(|selected| world.get_entity(selected).is_err()) {
+        world.resource_mut::<RestorePreviousResource>().selected = None;
+        world.resource_mut::<ActiveStyleInspection>().entity = None;
+    }
+}
+
+fn select(world: &mut World, entity: Entity) {
+    world.resource_mut::<RestorePreviousResource>().selected = Some(entity);
+    world.resource_mut::<ActiveStyleInspection>().entity = Some(entity);
+}
+
+fn add_child(world: &mut World, parent: Entity, child: Entity) {
+    if world.get_entity(parent).is_err() {
+        world.despawn(child);
+        return;
+    }
+    world.entity_mut(parent).add_child(child);
+    select(world, child);
+}
+
+// Puts `entity` among the siblings of `sibling`, `offset` places after it. Next to a root it
+// stays a root.
+fn insert_next_to(world: &mut World, sibling: Entity, entity: Entity, offset: usize) {
+    let Some(parent) = world.get::<Parent>(sibling).map(Parent::get) else {
+        return;
+    };
+    let index = world
+        .get::<Children>(parent)
+        .and_then(|children| children.iter().position(|child| *child == sibling))
+        .unwrap_or(0);
+    world
+        .entity_mut(parent)
+        .insert_children(index + offset, &[entity]);
+}
+
+// Replaces the node with its children.
+fn unwrap(world: &mut World, entity: Entity) {
+    if world.get_entity(entity).is_err() {
+        return;
+    }
+    let children: Vec<Entity> = world
+        .get::<Children>(entity)
+        .map(|children| children.to_vec())
+        .unwrap_or_default();
+    match world.get::<Parent>(entity).map(Parent::get) {
+        Some(parent) => {
+            let index = world
+                .get::<Children>(parent)
+                .and_then(|siblings| siblings.iter().position(|sibling| *sibling == entity))
+                .unwrap_or(0);
+            world.entity_mut(parent).insert_children(index, &children);
+        }
+        None => {
+            for child in &children {
+                world.entity_mut(*child).remove_parent();
+            }
+        }
+    }
+    world.entity_mut(entity).despawn_recursive();
+    if let Some(first) = children.first() {
+        select(world, *first);
+    }
+}
+
+// Deep clones a subtree through reflection. Components that aren't registered with
+// `#[reflect(Component)]` can't be cloned and are left out.
+fn duplicate(world: &mut World, entity: Entity) -> Entity {
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    let registry = registry.read();
+    duplicate_subtree(world, &registry, entity)
+}
+
+fn duplicate_subtree(world: &mut World, registry: &TypeRegistry, entity: Entity) -> Entity {
+    let type_ids: Vec<TypeId> = world
+        .inspect_entity(entity)
+        .filter_map(|info| info.type_id())
+        // The hierarchy of the copy is rebuilt below.
+        .filter(|type_id| {
+            *type_id != TypeId::of::<Parent>() && *type_id != TypeId::of::<Children>()
+        })
+        .collect();
+    let components: Vec<(ReflectComponent, Box<dyn PartialReflect>)> = type_ids
+        .into_iter()
+        .filter_map(|type_id| {
+            let reflect_component = registry.get_type_data::<ReflectComponent>(type_id)?;
+            let value = reflect_component.reflect(world.entity(entity))?;
+            Some((reflect_component.clone(), value.clone_value()))
+        })
+        .collect();
+
+    let mut copy = world.spawn_empty();
+    for (reflect_component, value) in components {
+        reflect_component.insert(&mut copy, value.as_ref(), registry);
+    }
+    let copy = copy.id();
+
+    let children: Vec<Entity> = world
+        .get::<Children>(entity)
+        .map(|children| children.to_vec())
+        .unwrap_or_default();
+    for child in children {
+        let child_copy = duplicate_subtree(world, registry, child);
+        world.entity_mut(copy).add_child(child_copy);
+    }
+    copy
+}
+
+// Dropping next to a root turns the node into a root drawn by the same camera. Roots are
+// ordered by how they stack, not by where they are dropped.
+fn move_to_root_level(world: &mut World, entity: Entity, root: Entity) {
+    if world.get::<Parent>(entity).is_none() {
+        warn!("bevy_ui_inspector: roots are drawn in stacking order, give {entity} a ZIndex or GlobalZIndex to move it");
+        return;
+    }
+    let target_camera = world.get::<TargetCamera>(root).cloned();
+    let mut entity = world.entity_mut(entity);
+    entity.remove_parent();
+    match target_camera {
+        Some(target_camera) => entity.insert(target_camera),
+        None => entity.remove::<TargetCamera>(),
+    };
+}
+
+fn move_node(world: &mut World, entity: Entity, target: Entity, position: DropPosition) {
+    if world.get_entity(entity).is_err() || world.get_entity(target).is_err() {
+        return;
+    }
+    let new_parent = match position {
+        DropPosition::Inside => Some(target),
+        DropPosition::Before | DropPosition::After => world.get::<Parent>(target).map(Parent::get),
+    };
+    let Some(new_parent) = new_parent else {
+        move_to_root_level(world, entity, target);
+        return;
+    };
+    // A node can't be moved into its own subtree.
+    let mut ancestor = Some(new_parent);
+    while let Some(current) = ancestor {
+        if current == entity {
+            warn!("bevy_ui_inspector: can't move {entity} into its own subtree");
+            return;
+        }
+        ancestor = world.get::<Parent>(current).map(Parent::get);
+    }
+
+    match position {
+        DropPosition::Inside => {
+            world.entity_mut(target).add_child(entity);
+        }
+        DropPosition::Before | DropPosition::After => {
+            // `insert_children` takes the index among the siblings without the moved node.
+            let index = world
+                .get::<Children>(new_parent)
+                .and_then(|children| {
+                    children
+                        .iter()
+                        .filter(|child| **child != entity)
+                        .position(|child| *child == target)
+                })
+                .unwrap_or(0);
+            let index = if position == DropPosition::After {
+                index + 1
+            } else {
+                index
+            };
+            world
+                .entity_mut(new_parent)
+                .insert_children(index, &[entity]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Two roots, the first with children `a`, `b` and `c`, the second with `d`.
+    fn world() -> (World, [Entity; 2], [Entity; 4]) {
+        let mut world = World::new();
+        let nodes = [(); 4].map(|_| world.spawn(Node::default()).id());
+        let first = world.spawn(Node::default()).id();
+        world.entity_mut(first).add_children(&nodes[..3]);
+        let camera = world.spawn_empty().id();
+        let second = world.spawn((Node::default(), TargetCamera(camera))).id();
+        world.entity_mut(second).add_child(nodes[3]);
+        (world, [first, second], nodes)
+    }
+
+    fn children(world: &World, parent: Entity) -> Vec<Entity> {
+        world
+            .get::<Children>(parent)
+            .map(|children| children.to_vec())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn moves_within_the_same_parent() {
+        let (mut world, [first, _], [a, b, c, _]) = world();
+        move_node(&mut world, a, c, DropPosition::After);
+        assert_eq!(children(&world, first), [b, c, a]);
+        move_node(&mut world, a, b, DropPosition::Before);
+        assert_eq!(children(&world, first), [a, b, c]);
+        move_node(&mut world, c, a, DropPosition::After);
+        assert_eq!(children(&world, first), [a, c, b]);
+        move_node(&mut world, b, a, DropPosition::Inside);
+        assert_eq!(children(&world, first), [a, c]);
+        assert_eq!(children(&world, a), [b]);
+    }
+
+    #[test]
+    fn moves_across_parents() {
+        let (mut world, [first, second], [a, b, c, d]) = world();
+        move_node(&mut world, d, b, DropPosition::Before);
+        assert_eq!(children(&world, first), [a, d, b, c]);
+        assert!(children(&world, second).is_empty());
+        move_node(&mut world, a, second, DropPosition::Inside);
+        assert_eq!(children(&world, first), [d, b, c]);
+        assert_eq!(children(&world, second), [a]);
+    }
+
+    #[test]
+    fn rejects_moving_into_its_own_subtree() {
+        let (mut world, [first, _], [a, b, c, _]) = world();
+        move_node(&mut world, first, a, DropPosition::Inside);
+        move_node(&mut world, first, b, DropPosition::After);
+        assert!(world.get::<Parent>(first).is_none());
+        assert_eq!(children(&world, first), [a, b, c]);
+        move_node(&mut world, a, a, DropPosition::Inside);
+        assert!(children(&world, a).is_empty());
+    }
+
+    #[test]
+    fn moving_next_to_a_root_makes_a_root_for_its_camera() {
+        let (mut world, [first, second], [a, b, c, d]) = world();
+        move_node(&mut world, a, second, DropPosition::Before);
+        assert!(world.get::<Parent>(a).is_none());
+        assert_eq!(
+            world.get::<TargetCamera>(a),
+            world.get::<TargetCamera>(second)
+        );
+        assert_eq!(children(&world, first), [b, c]);
+
+        move_node(&mut world, d, first, DropPosition::After);
+        assert!(world.get::<Parent>(d).is_none());
+        assert!(world.get::<TargetCamera>(d).is_none());
+    }
+}
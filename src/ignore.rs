@@ -0,0 +1,102 @@
+use std::sync::Arc;
+
+use bevy::{prelude::*, utils::HashSet};
+
+use crate::InspectorOverlayMarker;
+
+/// Hides a node and everything below it from the inspector: it isn't listed in the hierarchy
+/// and can't be picked, unless "show ignored" is checked.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct UiInspectorIgnore;
+
+/// Decides which nodes the inspector leaves out, next to [`UiInspectorIgnore`].
+pub(crate) type IgnoreFilter = Arc<dyn Fn(EntityRef) -> bool + Send + Sync>;
+
+#[derive(Resource, Default)]
+pub(crate) struct IgnoreSettings {
+    pub filter: Option<IgnoreFilter>,
+}
+
+#[derive(Resource, Default)]
+pub(crate) struct IgnoredNodes {
+    // Lists ignored nodes anyway, for debugging the inspector or the filter.
+    pub show: bool,
+    ignored: HashSet<Entity>,
+}
+
+impl IgnoredNodes {
+    pub fn is_hidden(&self, entity: Entity) -> bool {
+        !self.show && self.ignored.contains(&entity)
+    }
+
+    // Whether the node is ignored, even when ignored nodes are shown.
+    pub fn is_ignored(&self, entity: Entity) -> bool {
+        self.ignored.contains(&entity)
+    }
+
+    pub fn hidden(&self) -> HashSet<Entity> {
+        if self.show {
+            HashSet::new()
+        } else {
+            self.ignored.clone()
+        }
+    }
+}
+
+type HierarchyChanged = (
+    With<Node>,
+    Or<(
+        Added<Node>,
+        Added<UiInspectorIgnore>,
+        Changed<Children>,
+        Changed<Parent>,
+    )>,
+);
+
+// Whether the hierarchy or the ignore settings changed since the ignored nodes were collected.
+// The filter is only run again then, not when the other components it looks at change.
+pub(crate) fn ignored_nodes_outdated(
+    settings: Res<IgnoreSettings>,
+    changed_q: Query<(), HierarchyChanged>,
+    mut removed_ignore: RemovedComponents<UiInspectorIgnore>,
+    mut removed_parent: RemovedComponents<Parent>,
+    mut removed_node: RemovedComponents<Node>,
+) -> bool {
+    // Every removal is read, so none are left over for the next frame.
+    let removed = removed_ignore
+        .read()
+        .chain(removed_parent.read())
+        .chain(removed_node.read())
+        .count()
+        > 0;
+    removed || settings.is_changed() || !changed_q.is_empty()
+}
+
+// Collects ignored nodes from the roots down, as ignoring a node also ignores its subtree.
+pub(crate) fn update_ignored_nodes(world: &mut World) {
+    let filter = world.resource::<IgnoreSettings>().filter.clone();
+    let mut roots = world.query_filtered::<Entity, (With<Node>, Without<Parent>)>();
+    let mut stack: Vec<(Entity, bool)> = roots.iter(world).map(|root| (root, false)).collect();
+    let mut ignored = HashSet::new();
+    while let Some((entity, parent_ignored)) = stack.pop() {
+        let Ok(entity_ref) = world.get_entity(entity) else {
+            continue;
+        };
+        let is_ignored = parent_ignored
+            || entity_ref.contains::<UiInspectorIgnore>()
+            || entity_ref.contains::<InspectorOverlayMarker>()
+            || filter.as_ref().is_some_and(|filter| filter(entity_ref));
+        if is_ignored {
+            ignored.insert(entity);
+        }
+        if let Some(children) = entity_ref.get::<Children>() {
+            stack.extend(children.iter().map(|child| (*child, is_ignored)));
+        }
+    }
+    // Only touched when the set changes, so picking knows when to rebuild its index.
+    let mut ignored_nodes = world.resource_mut::<IgnoredNodes>();
+    if ignored_nodes.ignored != ignored {
+        ignored_nodes.ignored = ignored;
+    }
+}
@@ -0,0 +1,834 @@
+use bevy::{
+    prelude::*,
+    reflect::{ReflectRef, Struct},
+};
+use bevy_egui::{egui, EguiContexts};
+
+use crate::{
+    box_model::BoxModel, format_px, paint_label, window_to_gizmo, OverlaySettings,
+    RestorePreviousResource, UiInspectorGizmos,
+};
+
+const TRACK_COLOR: Color = Color::srgba(0.58, 0.26, 0.84, 0.9);
+const GAP_COLOR: Color = Color::srgba(0.58, 0.26, 0.84, 0.4);
+const AXIS_COLOR: Color = Color::srgb(1.0, 0.6, 0.0);
+const BASIS_COLOR: Color = Color::srgb(0.2, 0.8, 0.4);
+const LABEL_COLOR: egui::Color32 = egui::Color32::from_rgb(148, 66, 214);
+
+// Draws the tracks and gaps of the selected flex or grid container, the main axis of a flex
+// container and the flex-basis of its children.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn draw_layout_overlay(
+    windows: Query<&Window>,
+    previous_resource: Res<RestorePreviousResource>,
+    settings: Res<OverlaySettings>,
+    ui_scale: Res<UiScale>,
+    container_q: Query<(&Node, &ComputedNode, &GlobalTransform, &Children)>,
+    child_q: Query<(&Node, &ComputedNode, &GlobalTransform)>,
+    mut gizmos: Gizmos<UiInspectorGizmos>,
+    mut contexts: EguiContexts,
+) {
+    if !settings.layout {
+        return;
+    }
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let Some((style, node, tf, children)) = previous_resource
+        .selected
+        .and_then(|selected| container_q.get(selected).ok())
+    else {
+        return;
+    };
+    if !matches!(style.display, Display::Flex | Display::Grid) {
+        return;
+    }
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+    let scale = ui_scale.0;
+    let to_gizmo = |p: Vec2| window_to_gizmo(window, p * scale);
+    let label = |p: Vec2, text: String| {
+        let p = p * scale;
+        paint_label(
+            ctx,
+            "ui_inspector_layout",
+            egui::pos2(p.x, p.y),
+            text,
+            LABEL_COLOR,
+        );
+    };
+
+    let viewport = window.size() / scale;
+    let content = BoxModel::new(style, node, tf, None, viewport).content_box();
+    // Only in-flow children take part in the tracks and lines.
+    let items: Vec<(&Node, Rect)> = children
+        .iter()
+        .filter_map(|child| child_q.get(*child).ok())
+        .filter(|(child_style, _, _)| {
+            child_style.display != Display::None
+                && child_style.position_type != PositionType::Absolute
+        })
+        .map(|(child_style, child_node, child_tf)| {
+            let rect =
+                BoxModel::new(child_style, child_node, child_tf, Some(node), viewport).margin_box();
+            (child_style, rect)
+        })
+        .collect();
+    let column_gap = style
+        .column_gap
+        .resolve(content.width(), viewport)
+        .unwrap_or(0.0);
+    let row_gap = style
+        .row_gap
+        .resolve(content.height(), viewport)
+        .unwrap_or(0.0);
+
+    if style.display == Display::Grid {
+        let column_sizes = track_sizes(
+            &style.grid_template_columns,
+            content.width(),
+            column_gap,
+            viewport,
+        );
+        let row_sizes = track_sizes(
+            &style.grid_template_rows,
+            content.height(),
+            row_gap,
+            viewport,
+        );
+        let auto_columns: Vec<_> = style
+            .grid_auto_columns
+            .iter()
+            .map(|track| track_size(track, content.width(), viewport))
+            .collect();
+        let auto_rows: Vec<_> = style
+            .grid_auto_rows
+            .iter()
+            .map(|track| track_size(track, content.height(), viewport))
+            .collect();
+        let columns = tracks(
+            &GridAxis {
+                start: content.min.x,
+                size: content.width(),
+                gap: column_gap,
+                explicit: &column_sizes,
+                implicit: &auto_columns,
+                distribution: style.justify_content.into(),
+            },
+            &items
+                .iter()
+                .map(|(_, rect)| (rect.min.x, rect.max.x))
+                .collect::<Vec<_>>(),
+        );
+        let rows = tracks(
+            &GridAxis {
+                start: content.min.y,
+                size: content.height(),
+                gap: row_gap,
+                explicit: &row_sizes,
+                implicit: &auto_rows,
+                distribution: style.align_content.into(),
+            },
+            &items
+                .iter()
+                .map(|(_, rect)| (rect.min.y, rect.max.y))
+                .collect::<Vec<_>>(),
+        );
+
+        for track in &columns {
+            let area = Rect::new(track.start, content.min.y, track.end, content.max.y);
+            if track.is_gap {
+                hatch(&mut gizmos, area, GAP_COLOR, to_gizmo);
+                continue;
+            }
+            for x in [track.start, track.end] {
+                gizmos.line_2d(
+                    to_gizmo(Vec2::new(x, content.min.y)),
+                    to_gizmo(Vec2::new(x, content.max.y)),
+                    TRACK_COLOR,
+                );
+            }
+            label(
+                Vec2::new((track.start + track.end) / 2.0, content.min.y + 8.0),
+                format_px(track.end - track.start),
+            );
+        }
+        for track in &rows {
+            let area = Rect::new(content.min.x, track.start, content.max.x, track.end);
+            if track.is_gap {
+                hatch(&mut gizmos, area, GAP_COLOR, to_gizmo);
+                continue;
+            }
+            for y in [track.start, track.end] {
+                gizmos.line_2d(
+                    to_gizmo(Vec2::new(content.min.x, y)),
+                    to_gizmo(Vec2::new(content.max.x, y)),
+                    TRACK_COLOR,
+                );
+            }
+            label(
+                Vec2::new(content.min.x + 16.0, (track.start + track.end) / 2.0),
+                format_px(track.end - track.start),
+            );
+        }
+        // Grid lines are numbered like the css `grid-column` / `grid-row` lines.
+        let column_tracks: Vec<_> = columns.iter().filter(|track| !track.is_gap).collect();
+        for (i, track) in column_tracks.iter().enumerate() {
+            label(
+                Vec2::new(track.start, content.min.y - 8.0),
+                (i + 1).to_string(),
+            );
+            if i + 1 == column_tracks.len() {
+                label(
+                    Vec2::new(track.end, content.min.y - 8.0),
+                    (i + 2).to_string(),
+                );
+            }
+        }
+        let row_tracks: Vec<_> = rows.iter().filter(|track| !track.is_gap).collect();
+        for (i, track) in row_tracks.iter().enumerate() {
+            label(
+                Vec2::new(content.min.x - 8.0, track.start),
+                (i + 1).to_string(),
+            );
+            if i + 1 == row_tracks.len() {
+                label(
+                    Vec2::new(content.min.x - 8.0, track.end),
+                    (i + 2).to_string(),
+                );
+            }
+        }
+        return;
+    }
+
+    let is_row = matches!(
+        style.flex_direction,
+        FlexDirection::Row | FlexDirection::RowReverse
+    );
+    // Flex lines are found in (main, cross) spans, `area` turns them back into a rect.
+    let area = |main: Span, cross: Span| {
+        if is_row {
+            Rect::new(main.0, cross.0, main.1, cross.1)
+        } else {
+            Rect::new(cross.0, main.0, cross.1, main.1)
+        }
+    };
+    let spans = |rect: &Rect| {
+        if is_row {
+            ((rect.min.x, rect.max.x), (rect.min.y, rect.max.y))
+        } else {
+            ((rect.min.y, rect.max.y), (rect.min.x, rect.max.x))
+        }
+    };
+    let (main_gap, cross_gap) = if is_row {
+        (column_gap, row_gap)
+    } else {
+        (row_gap, column_gap)
+    };
+    let (content_main, content_cross) = spans(&content);
+    let lines = flex_lines(
+        &items
+            .iter()
+            .map(|(_, rect)| spans(rect))
+            .collect::<Vec<_>>(),
+        main_gap,
+        content_cross,
+        style.flex_wrap != FlexWrap::NoWrap,
+    );
+    for (i, line) in lines.iter().enumerate() {
+        let line_area = area(content_main, line.cross);
+        gizmos.rect_2d(
+            Isometry2d::from_translation(to_gizmo(line_area.center())),
+            line_area.size() * scale,
+            TRACK_COLOR,
+        );
+        let middle = (line.cross.0 + line.cross.1) / 2.0;
+        label(
+            area((content_main.0 + 16.0, 0.0), (middle, 0.0)).min,
+            format_px(line.cross.1 - line.cross.0),
+        );
+        for gap in &line.gaps {
+            hatch(&mut gizmos, area(*gap, line.cross), GAP_COLOR, to_gizmo);
+        }
+        if i + 1 < lines.len() && cross_gap > 0.0 {
+            let gap = (line.cross.1, line.cross.1 + cross_gap);
+            hatch(&mut gizmos, area(content_main, gap), GAP_COLOR, to_gizmo);
+        }
+    }
+
+    let center = content.center();
+    let (start, end) = match style.flex_direction {
+        FlexDirection::Row => (
+            Vec2::new(content.min.x, center.y),
+            Vec2::new(content.max.x, center.y),
+        ),
+        FlexDirection::RowReverse => (
+            Vec2::new(content.max.x, center.y),
+            Vec2::new(content.min.x, center.y),
+        ),
+        FlexDirection::Column => (
+            Vec2::new(center.x, content.min.y),
+            Vec2::new(center.x, content.max.y),
+        ),
+        FlexDirection::ColumnReverse => (
+            Vec2::new(center.x, content.max.y),
+            Vec2::new(center.x, content.min.y),
+        ),
+    };
+    gizmos.arrow_2d(to_gizmo(start), to_gizmo(end), AXIS_COLOR);
+
+    let is_reverse = matches!(
+        style.flex_direction,
+        FlexDirection::RowReverse | FlexDirection::ColumnReverse
+    );
+    let main_size = if is_row {
+        content.width()
+    } else {
+        content.height()
+    };
+    for (child_style, rect) in &items {
+        let final_size = if is_row { rect.width() } else { rect.height() };
+        let size_val = if is_row {
+            child_style.width
+        } else {
+            child_style.height
+        };
+        // An `auto` flex-basis falls back to the main size, and to the content size after that.
+        let basis_val = match child_style.flex_basis {
+            Val::Auto => size_val,
+            basis => basis,
+        };
+        let basis = basis_val
+            .resolve(main_size, viewport)
+            .ok()
+            .filter(|_| basis_val != Val::Auto);
+
+        if let Some(basis) = basis {
+            let (from, to) = match (is_row, is_reverse) {
+                (true, false) => (rect.min, rect.min + Vec2::new(basis, 0.0)),
+                (true, true) => (
+                    Vec2::new(rect.max.x, rect.min.y),
+                    Vec2::new(rect.max.x - basis, rect.min.y),
+                ),
+                (false, false) => (rect.min, rect.min + Vec2::new(0.0, basis)),
+                (false, true) => (
+                    Vec2::new(rect.min.x, rect.max.y),
+                    Vec2::new(rect.min.x, rect.max.y - basis),
+                ),
+            };
+            gizmos.line_2d(to_gizmo(from), to_gizmo(to), BASIS_COLOR);
+        }
+        label(
+            Vec2::new(rect.center().x, rect.max.y - 8.0),
+            format!(
+                "{} → {}",
+                basis.map(format_px).unwrap_or("auto".to_string()),
+                format_px(final_size)
+            ),
+        );
+    }
+}
+
+// Start and end along one axis.
+type Span = (f32, f32);
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum TrackSize {
+    Fixed(f32),
+    Fraction(f32),
+    // Sized by the items in the track.
+    Content,
+}
+
+// How the free space of a grid axis is spread, from `justify-content` or `align-content`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Distribution {
+    Start,
+    End,
+    Center,
+    Stretch,
+    SpaceBetween,
+    SpaceAround,
+    SpaceEvenly,
+}
+
+impl From<JustifyContent> for Distribution {
+    fn from(value: JustifyContent) -> Self {
+        match value {
+            JustifyContent::Default | JustifyContent::Stretch => Distribution::Stretch,
+            JustifyContent::Start | JustifyContent::FlexStart => Distribution::Start,
+            JustifyContent::End | JustifyContent::FlexEnd => Distribution::End,
+            JustifyContent::Center => Distribution::Center,
+            JustifyContent::SpaceBetween => Distribution::SpaceBetween,
+            JustifyContent::SpaceAround => Distribution::SpaceAround,
+            JustifyContent::SpaceEvenly => Distribution::SpaceEvenly,
+        }
+    }
+}
+
+impl From<AlignContent> for Distribution {
+    fn from(value: AlignContent) -> Self {
+        match value {
+            AlignContent::Default | AlignContent::Stretch => Distribution::Stretch,
+            AlignContent::Start | AlignContent::FlexStart => Distribution::Start,
+            AlignContent::End | AlignContent::FlexEnd => Distribution::End,
+            AlignContent::Center => Distribution::Center,
+            AlignContent::SpaceBetween => Distribution::SpaceBetween,
+            AlignContent::SpaceAround => Distribution::SpaceAround,
+            AlignContent::SpaceEvenly => Distribution::SpaceEvenly,
+        }
+    }
+}
+
+// One axis of a grid container's content box.
+struct GridAxis<'a> {
+    start: f32,
+    size: f32,
+    gap: f32,
+    // `grid-template-*` with the repetitions expanded.
+    explicit: &'a [TrackSize],
+    // `grid-auto-*`, cycled through for tracks past the explicit grid.
+    implicit: &'a [TrackSize],
+    distribution: Distribution,
+}
+
+#[derive(PartialEq, Debug)]
+struct Track {
+    start: f32,
+    end: f32,
+    // The `column-gap` or `row-gap` after a track.
+    is_gap: bool,
+}
+
+// Lays out the tracks of a grid axis like taffy does. Content sized tracks can't be resolved
+// without the items' content, so they are measured from the items starting in them; the
+// `items` are the margin boxes of the children along the axis.
+fn tracks(axis: &GridAxis, items: &[Span]) -> Vec<Track> {
+    let near = |a: f32, b: f32| (a - b).abs() < 0.5;
+    let kind = |i: usize| {
+        axis.explicit.get(i).copied().unwrap_or_else(|| {
+            let implicit = i - axis.explicit.len();
+            axis.implicit
+                .get(implicit % axis.implicit.len().max(1))
+                .copied()
+                .unwrap_or(TrackSize::Content)
+        })
+    };
+    // Content sized tracks are as big as the largest item starting in them, up to where the
+    // next item starts.
+    let measure = |cursor: f32| {
+        let next_start = items
+            .iter()
+            .map(|(start, _)| *start)
+            .filter(|start| *start > cursor + 0.5)
+            .min_by(f32::total_cmp);
+        items
+            .iter()
+            .filter(|(start, _)| near(*start, cursor))
+            .map(|(_, end)| next_start.map_or(*end, |next| end.min(next - axis.gap)) - cursor)
+            .fold(0.0, f32::max)
+    };
+
+    let mut count = axis.explicit.len();
+    let mut content_sizes: Vec<f32> = Vec::new();
+    let mut layout: Vec<Span> = Vec::new();
+    // Content sizes and fractions depend on each other, a few passes settle them.
+    for _ in 0..4 {
+        content_sizes.resize(count, 0.0);
+        let sizes: Vec<f32> = (0..count)
+            .map(|i| match kind(i) {
+                TrackSize::Fixed(size) => size,
+                TrackSize::Content => content_sizes[i],
+                TrackSize::Fraction(_) => 0.0,
+            })
+            .collect();
+        let gaps = axis.gap * count.saturating_sub(1) as f32;
+        let mut free = axis.size - sizes.iter().sum::<f32>() - gaps;
+        let fractions: f32 = (0..count)
+            .filter_map(|i| match kind(i) {
+                TrackSize::Fraction(fraction) => Some(fraction),
+                _ => None,
+            })
+            .sum();
+        let fraction_size = if fractions > 0.0 {
+            let size = free.max(0.0) / fractions.max(1.0);
+            free = 0.0;
+            size
+        } else {
+            0.0
+        };
+        let free = free.max(0.0);
+        let n = count as f32;
+        // Stretched content tracks are measured at their stretched size already.
+        let (offset, spacing) = match axis.distribution {
+            Distribution::Start | Distribution::Stretch => (0.0, 0.0),
+            Distribution::End => (free, 0.0),
+            Distribution::Center => (free / 2.0, 0.0),
+            Distribution::SpaceBetween if count > 1 => (0.0, free / (n - 1.0)),
+            Distribution::SpaceBetween => (0.0, 0.0),
+            Distribution::SpaceAround => (free / n / 2.0, free / n),
+            Distribution::SpaceEvenly => (free / (n + 1.0), free / (n + 1.0)),
+        };
+
+        let mut cursor = axis.start + offset;
+        let mut settled = true;
+        layout.clear();
+        for (i, content_size) in content_sizes.iter_mut().enumerate() {
+            let size = match kind(i) {
+                TrackSize::Fixed(size) => size,
+                TrackSize::Fraction(fraction) => fraction * fraction_size,
+                TrackSize::Content => {
+                    let size = measure(cursor);
+                    settled &= near(size, *content_size);
+                    *content_size = size;
+                    size
+                }
+            };
+            layout.push((cursor, cursor + size));
+            cursor += size + axis.gap + spacing;
+        }
+        // Items past the explicit grid are placed in implicit tracks.
+        let end = layout.last().map_or(axis.start, |(_, end)| *end);
+        let mut starts: Vec<f32> = items
+            .iter()
+            .map(|(start, _)| *start)
+            .filter(|start| *start > end + 0.5 || (count == 0 && near(*start, end)))
+            .collect();
+        starts.sort_by(f32::total_cmp);
+        starts.dedup_by(|a, b| near(*a, *b));
+        if !starts.is_empty() {
+            count += starts.len();
+            settled = false;
+        }
+        if settled {
+            break;
+        }
+    }
+
+    let mut tracks = Vec::new();
+    for (i, (start, end)) in layout.iter().enumerate() {
+        tracks.push(Track {
+            start: *start,
+            end: *end,
+            is_gap: false,
+        });
+        if i + 1 < layout.len() && axis.gap > 0.0 {
+            tracks.push(Track {
+                start: *end,
+                end: end + axis.gap,
+                is_gap: true,
+            });
+        }
+    }
+    tracks
+}
+
+// `grid-template-*` with every repetition written out.
+fn track_sizes(
+    template: &[RepeatedGridTrack],
+    available: f32,
+    gap: f32,
+    viewport: Vec2,
+) -> Vec<TrackSize> {
+    // The fields of grid tracks are private, they are read through reflection.
+    let repeated: Vec<(GridTrackRepetition, Vec<TrackSize>)> = template
+        .iter()
+        .map(|repeated| {
+            let repetition = repeated
+                .field("repetition")
+                .and_then(|field| field.try_downcast_ref::<GridTrackRepetition>())
+                .copied()
+                .unwrap_or_default();
+            let sizes = match repeated.field("tracks").map(PartialReflect::reflect_ref) {
+                Some(ReflectRef::List(list)) => list
+                    .iter()
+                    .filter_map(|track| track.try_downcast_ref::<GridTrack>())
+                    .map(|track| track_size(track, available, viewport))
+                    .collect(),
+                _ => Vec::new(),
+            };
+            (repetition, sizes)
+        })
+        .collect();
+
+    // `auto-fill` and `auto-fit` repeat as often as their fixed tracks fit.
+    let fixed = |sizes: &[TrackSize]| {
+        sizes
+            .iter()
+            .map(|size| match size {
+                TrackSize::Fixed(size) => *size,
+                _ => 0.0,
+            })
+            .sum::<f32>()
+    };
+    let (outside_size, outside_count) = repeated
+        .iter()
+        .filter_map(|(repetition, sizes)| match repetition {
+            GridTrackRepetition::Count(count) => {
+                Some((fixed(sizes) * *count as f32, sizes.len() * *count as usize))
+            }
+            _ => None,
+        })
+        .fold((0.0, 0), |(size, count), (s, c)| (size + s, count + c));
+    let room = available - outside_size - gap * outside_count as f32 + gap;
+
+    repeated
+        .into_iter()
+        .flat_map(|(repetition, sizes)| {
+            let count = match repetition {
+                GridTrackRepetition::Count(count) => count as usize,
+                GridTrackRepetition::AutoFill | GridTrackRepetition::AutoFit => {
+                    let per_repetition = fixed(&sizes) + gap * sizes.len() as f32;
+                    if per_repetition > 0.0 {
+                        ((room / per_repetition).floor() as usize).max(1)
+                    } else {
+                        1
+                    }
+                }
+            };
+            std::iter::repeat_n(sizes, count).flatten()
+        })
+        .collect()
+}
+
+fn track_size(track: &GridTrack, available: f32, viewport: Vec2) -> TrackSize {
+    let max = track
+        .field("max_sizing_function")
+        .and_then(|field| field.try_downcast_ref::<MaxTrackSizingFunction>())
+        .copied()
+        .unwrap_or_default();
+    match max {
+        MaxTrackSizingFunction::Px(size) => TrackSize::Fixed(size),
+        MaxTrackSizingFunction::Percent(percent) => TrackSize::Fixed(available * percent / 100.0),
+        MaxTrackSizingFunction::Vw(percent) => TrackSize::Fixed(viewport.x * percent / 100.0),
+        MaxTrackSizingFunction::Vh(percent) => TrackSize::Fixed(viewport.y * percent / 100.0),
+        MaxTrackSizingFunction::VMin(percent) => {
+            TrackSize::Fixed(viewport.min_element() * percent / 100.0)
+        }
+        MaxTrackSizingFunction::VMax(percent) => {
+            TrackSize::Fixed(viewport.max_element() * percent / 100.0)
+        }
+        MaxTrackSizingFunction::Fraction(fraction) => TrackSize::Fraction(fraction),
+        MaxTrackSizingFunction::MinContent
+        | MaxTrackSizingFunction::MaxContent
+        | MaxTrackSizingFunction::FitContentPx(_)
+        | MaxTrackSizingFunction::FitContentPercent(_)
+        | MaxTrackSizingFunction::Auto => TrackSize::Content,
+    }
+}
+
+#[derive(PartialEq, Debug)]
+struct FlexLine {
+    cross: Span,
+    // The `gap` between neighbouring items along the main axis.
+    gaps: Vec<Span>,
+}
+
+// Groups the items of a flex container into its lines, ordered along the cross axis. Items are
+// given as (main, cross) spans of their margin boxes, in the order they are laid out. A line
+// that doesn't wrap fills the container's cross size.
+fn flex_lines(
+    items: &[(Span, Span)],
+    main_gap: f32,
+    content_cross: Span,
+    wrap: bool,
+) -> Vec<FlexLine> {
+    let overlaps = |a: Span, b: Span| a.0 < b.1 - 0.5 && b.0 < a.1 - 0.5;
+    let mut lines: Vec<(Span, Vec<Span>)> = Vec::new();
+    for (main, cross) in items {
+        match lines.last_mut() {
+            Some((line_cross, mains)) if !wrap || overlaps(*line_cross, *cross) => {
+                *line_cross = (line_cross.0.min(cross.0), line_cross.1.max(cross.1));
+                mains.push(*main);
+            }
+            _ => lines.push((*cross, vec![*main])),
+        }
+    }
+    if !wrap {
+        if let Some((line_cross, _)) = lines.first_mut() {
+            *line_cross = content_cross;
+        }
+    }
+    lines.sort_by(|(a, _), (b, _)| a.0.total_cmp(&b.0));
+    lines
+        .into_iter()
+        .map(|(cross, mut mains)| {
+            mains.sort_by(|a, b| a.0.total_cmp(&b.0));
+            let gaps = if main_gap > 0.0 {
+                mains
+                    .windows(2)
+                    .map(|pair| (pair[0].1, pair[0].1 + main_gap))
+                    .collect()
+            } else {
+                Vec::new()
+            };
+            FlexLine { cross, gaps }
+        })
+        .collect()
+}
+
+// Gizmos can't fill, so areas are shaded with diagonal lines like the Firefox grid inspector.
+pub(crate) fn hatch(
+    gizmos: &mut Gizmos<UiInspectorGizmos>,
+    rect: Rect,
+    color: Color,
+    to_gizmo: impl Fn(Vec2) -> Vec2,
+) {
+    let (width, height) = (rect.width(), rect.height());
+    let mut t = 6.0;
+    while t < width + height {
+        let from = if t <= width {
+            Vec2::new(rect.min.x + t, rect.min.y)
+        } else {
+            Vec2::new(rect.max.x, rect.min.y + t - width)
+        };
+        let to = if t <= height {
+            Vec2::new(rect.min.x, rect.min.y + t)
+        } else {
+            Vec2::new(rect.min.x + t - height, rect.max.y)
+        };
+        gizmos.line_2d(to_gizmo(from), to_gizmo(to), color);
+        t += 6.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn axis<'a>(
+        size: f32,
+        gap: f32,
+        explicit: &'a [TrackSize],
+        distribution: Distribution,
+    ) -> GridAxis<'a> {
+        GridAxis {
+            start: 0.0,
+            size,
+            gap,
+            explicit,
+            implicit: &[],
+            distribution,
+        }
+    }
+
+    fn track(start: f32, end: f32) -> Track {
+        Track {
+            start,
+            end,
+            is_gap: false,
+        }
+    }
+
+    fn gap(start: f32, end: f32) -> Track {
+        Track {
+            start,
+            end,
+            is_gap: true,
+        }
+    }
+
+    #[test]
+    fn fixed_tracks_are_separated_by_the_gap() {
+        let sizes = [TrackSize::Fixed(100.0); 3];
+        assert_eq!(
+            tracks(&axis(400.0, 10.0, &sizes, Distribution::Start), &[]),
+            vec![
+                track(0.0, 100.0),
+                gap(100.0, 110.0),
+                track(110.0, 210.0),
+                gap(210.0, 220.0),
+                track(220.0, 320.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn distributed_free_space_is_not_a_gap() {
+        let sizes = [TrackSize::Fixed(100.0); 2];
+        assert_eq!(
+            tracks(&axis(300.0, 0.0, &sizes, Distribution::SpaceBetween), &[]),
+            vec![track(0.0, 100.0), track(200.0, 300.0)]
+        );
+        assert_eq!(
+            tracks(&axis(300.0, 10.0, &sizes, Distribution::SpaceBetween), &[]),
+            vec![track(0.0, 100.0), gap(100.0, 110.0), track(200.0, 300.0)]
+        );
+    }
+
+    #[test]
+    fn empty_tracks_are_kept() {
+        let sizes = [TrackSize::Fraction(1.0); 3];
+        assert_eq!(
+            tracks(
+                &axis(320.0, 10.0, &sizes, Distribution::Stretch),
+                &[(0.0, 100.0)]
+            ),
+            vec![
+                track(0.0, 100.0),
+                gap(100.0, 110.0),
+                track(110.0, 210.0),
+                gap(210.0, 220.0),
+                track(220.0, 320.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn content_tracks_are_measured_from_their_items() {
+        let sizes = [TrackSize::Content, TrackSize::Fraction(1.0)];
+        assert_eq!(
+            tracks(
+                &axis(300.0, 0.0, &sizes, Distribution::Stretch),
+                &[(0.0, 50.0), (50.0, 300.0)]
+            ),
+            vec![track(0.0, 50.0), track(50.0, 300.0)]
+        );
+    }
+
+    #[test]
+    fn items_past_the_explicit_grid_get_implicit_tracks() {
+        assert_eq!(
+            tracks(
+                &axis(100.0, 5.0, &[], Distribution::Stretch),
+                &[(0.0, 20.0), (25.0, 60.0)]
+            ),
+            vec![track(0.0, 20.0), gap(20.0, 25.0), track(25.0, 60.0)]
+        );
+    }
+
+    #[test]
+    fn a_line_that_does_not_wrap_fills_the_container() {
+        let items = [((0.0, 50.0), (0.0, 30.0)), ((60.0, 100.0), (0.0, 20.0))];
+        assert_eq!(
+            flex_lines(&items, 10.0, (0.0, 40.0), false),
+            vec![FlexLine {
+                cross: (0.0, 40.0),
+                gaps: vec![(50.0, 60.0)],
+            }]
+        );
+    }
+
+    #[test]
+    fn wrapped_lines_only_hatch_the_gap() {
+        let items = [
+            ((0.0, 50.0), (0.0, 30.0)),
+            ((150.0, 200.0), (0.0, 30.0)),
+            ((0.0, 50.0), (40.0, 70.0)),
+        ];
+        assert_eq!(
+            flex_lines(&items, 10.0, (0.0, 100.0), true),
+            vec![
+                FlexLine {
+                    cross: (0.0, 30.0),
+                    gaps: vec![(50.0, 60.0)],
+                },
+                FlexLine {
+                    cross: (40.0, 70.0),
+                    gaps: Vec::new(),
+                },
+            ]
+        );
+    }
+}
@@ -2,7 +2,8 @@ use bevy::{
     color::palettes::{css::RED, tailwind::BLUE_100},
     prelude::*,
     render::view::RenderLayers,
-    ui::NodeQuery,
+    sprite::BorderRect,
+    ui::{FocusPolicy, NodeQuery},
 };
 use bevy_egui::{
     egui::{self, Ui},
@@ -17,6 +18,24 @@ use bevy_egui::{
 // pub mod theme;
 // pub mod val;
 // pub mod val_input;
+use box_model::BoxModel;
+pub use hide::HideMode;
+pub use ignore::UiInspectorIgnore;
+mod box_model;
+mod hide;
+mod hierarchy_edit;
+mod ignore;
+mod layout_overlay;
+mod lint;
+mod measure;
+mod overflow;
+mod pick_index;
+mod pointer_pick;
+pub mod rulers;
+mod search;
+mod tree_model;
+mod tree_row;
+mod wireframe;
 
 #[derive(Default, Copy, PartialEq, Eq, Clone, Debug, Reflect)]
 pub enum ValTypes {
@@ -42,6 +61,37 @@ impl std::fmt::Display for ValTypes {
         }
     }
 }
+/// Gizmo group used for everything the inspector draws, so the app's own gizmo
+/// configuration is left alone.
+#[derive(Default, Reflect, GizmoConfigGroup)]
+pub struct UiInspectorGizmos;
+
+/// Which overlays are drawn on top of the app.
+#[derive(Resource)]
+pub struct OverlaySettings {
+    /// Tracks, gaps and flex axis of the selected flex or grid container.
+    pub layout: bool,
+    /// Outlines of every node in the app.
+    pub wireframe: bool,
+    /// Names of the nodes next to their wireframe outline.
+    pub wireframe_names: bool,
+    /// Overflowing children, clipped areas and text that doesn't fit, with a list of them.
+    pub overflow: bool,
+    /// A window listing likely layout mistakes.
+    pub problems: bool,
+}
+impl Default for OverlaySettings {
+    fn default() -> Self {
+        Self {
+            layout: true,
+            wireframe: false,
+            wireframe_names: false,
+            overflow: false,
+            problems: false,
+        }
+    }
+}
+
 #[derive(Resource, Default)]
 pub struct RestorePreviousResource {
     pub selected: Option<Entity>,
@@ -50,10 +100,13 @@ pub struct RestorePreviousResource {
 #[derive(Resource, Default)]
 pub struct ActiveStyleInspection {
     pub entity: Option<Entity>,
+    region: Option<HighlightRegion>,
 }
 #[derive(Resource, Default)]
 struct PickingUiNode {
     is_picking: bool,
+    // Node under the cursor while picking, with its rect in logical window pixels.
+    hovered: Option<(Entity, Rect)>,
 }
 
 fn val_dropdown(ui: &mut Ui, val: &mut ValTypes, id: &str) -> bool {
@@ -71,7 +124,8 @@ fn val_dropdown(ui: &mut Ui, val: &mut ValTypes, id: &str) -> bool {
     has_changed
 }
 
-fn val_input(ui: &mut Ui, val: &mut Val, id: &str) {
+// Returns whether the pointer is over the input.
+fn val_input(ui: &mut Ui, val: &mut Val, id: &str) -> bool {
     let mut original_val = match val {
         Val::Px(_) => ValTypes::Px,
         Val::Percent(_) => ValTypes::Percent,
@@ -88,19 +142,39 @@ fn val_input(ui: &mut Ui, val: &mut Val, id: &str) {
         _ => default,
     };
 
-    let input = ui.add(egui::DragValue::new(&mut v));
-    let type_changed = val_dropdown(ui, &mut original_val, id);
-    if input.changed() || type_changed {
-        *val = match original_val {
-            ValTypes::Auto => Val::Auto,
-            ValTypes::Px => Val::Px(v),
-            ValTypes::Percent => Val::Percent(v),
-            ValTypes::Vw => Val::Vw(v),
-            ValTypes::Vh => Val::Vh(v),
-            ValTypes::VMin => Val::VMin(v),
-            ValTypes::VMax => Val::VMax(v),
-        };
+    ui.scope(|ui| {
+        let input = ui.add(egui::DragValue::new(&mut v));
+        let type_changed = val_dropdown(ui, &mut original_val, id);
+        if input.changed() || type_changed {
+            *val = match original_val {
+                ValTypes::Auto => Val::Auto,
+                ValTypes::Px => Val::Px(v),
+                ValTypes::Percent => Val::Percent(v),
+                ValTypes::Vw => Val::Vw(v),
+                ValTypes::Vh => Val::Vh(v),
+                ValTypes::VMin => Val::VMin(v),
+                ValTypes::VMax => Val::VMax(v),
+            };
+        }
+    })
+    .response
+    .contains_pointer()
+}
+
+// The four inputs of a padding, margin or border row, returning the side under the pointer.
+fn sides_input(ui: &mut Ui, sides: &mut UiRect, id: &str) -> Option<Side> {
+    let mut hovered = None;
+    for (val, side, name) in [
+        (&mut sides.left, Side::Left, "left"),
+        (&mut sides.bottom, Side::Bottom, "bottom"),
+        (&mut sides.right, Side::Right, "right"),
+        (&mut sides.top, Side::Top, "top"),
+    ] {
+        if val_input(ui, val, &format!("{id}.{name}")) {
+            hovered = Some(side);
+        }
     }
+    hovered
 }
 
 macro_rules! enum_dropdown {
@@ -119,67 +193,123 @@ macro_rules! enum_dropdown {
     }};
 }
 
+type PickableNodes<'w, 's> = Query<
+    'w,
+    's,
+    (Entity, &'static GlobalTransform, &'static ComputedNode),
+    Without<InspectorOverlayMarker>,
+>;
+
+// Top level nodes, including the inspector's own.
+type AllUiRoots<'w, 's, D> = Query<'w, 's, D, (Without<Parent>, With<Node>)>;
+// Top level nodes of the app's UI.
+type UiRoots<'w, 's, D> =
+    Query<'w, 's, D, (Without<Parent>, Without<InspectorOverlayMarker>, With<Node>)>;
+
+// Converts a node's layout rect to logical window pixels, with the origin in the top left.
+fn node_window_rect(
+    window: &Window,
+    node: &ComputedNode,
+    global_transform: &GlobalTransform,
+) -> Rect {
+    // Layout already applies `UiScale` on top of the window scale factor.
+    let rect = Rect::from_center_size(global_transform.translation().truncate(), node.size());
+    Rect {
+        min: rect.min / window.scale_factor(),
+        max: rect.max / window.scale_factor(),
+    }
+}
+
+// Gizmos are drawn by a 2d camera centered on the window, with y pointing up.
+fn window_to_gizmo(window: &Window, point: Vec2) -> Vec2 {
+    Vec2::new(
+        point.x - window.width() / 2.0,
+        window.height() / 2.0 - point.y,
+    )
+}
+
+// Paints a small pixel label on top of the app, but below the inspector window.
+fn paint_label(ctx: &egui::Context, id: &str, pos: egui::Pos2, text: String, color: egui::Color32) {
+    let painter = ctx.layer_painter(egui::LayerId::new(
+        egui::Order::Background,
+        egui::Id::new(id),
+    ));
+    let galley = painter.layout_no_wrap(text, egui::FontId::monospace(11.0), egui::Color32::WHITE);
+    let rect = egui::Align2::CENTER_CENTER
+        .anchor_size(pos, galley.size())
+        .expand2(egui::vec2(3.0, 1.0));
+    painter.rect_filled(rect, 2.0, color);
+    painter.galley(
+        rect.shrink2(egui::vec2(3.0, 1.0)).min,
+        galley,
+        egui::Color32::WHITE,
+    );
+}
+
+fn format_px(value: f32) -> String {
+    let rounded = (value * 10.0).round() / 10.0;
+    if rounded.fract() == 0.0 {
+        format!("{:.0}", rounded)
+    } else {
+        format!("{:.1}", rounded)
+    }
+}
+
+fn pointer_over_egui(contexts: &mut EguiContexts) -> bool {
+    contexts
+        .try_ctx_mut()
+        .is_some_and(|ctx| ctx.wants_pointer_input())
+}
+
 fn ui_node_hit_test_system(
     windows: Query<&Window>,
     mouse_button_input: Res<ButtonInput<MouseButton>>,
-    node_query: Query<
-        (Entity, &GlobalTransform, &ComputedNode),
-        (Without<HoverUiElementWrapperMarker>, Without<HoverUiElementMarker>),
+    node_query: PickableNodes,
+    mut pick_index: ResMut<pick_index::PickIndex>,
+    pointer_pick: Res<pointer_pick::PointerPick>,
+    node_q: Query<
+        (&Node, &ComputedNode, &GlobalTransform, Option<&Parent>),
+        Without<InspectorOverlayMarker>,
     >,
-    node_q: Query<(&ComputedNode, &GlobalTransform)>,
+    parent_node_q: Query<&ComputedNode>,
     mut previous_resource: ResMut<RestorePreviousResource>,
     mut style_under_inspection: ResMut<ActiveStyleInspection>,
-    hovered_ui_wrapper_q: Query<Entity, With<HoverUiElementWrapperMarker>>,
-    hovered_ui_q: Query<(Entity, &Node), With<HoverUiElementMarker>>,
+    mut hovered_ui_wrapper_q: Query<&mut Visibility, With<HoverUiElementWrapperMarker>>,
+    mut hovered_ui_q: HighlightLayers,
     mut picking_ui_node: ResMut<PickingUiNode>,
-    mut gizmos: Gizmos,
-    mut commands: Commands,
+    mut gizmos: Gizmos<UiInspectorGizmos>,
+    mut contexts: EguiContexts,
     ui_scale: Res<UiScale>,
 ) {
     let window = windows.get_single().unwrap();
-    let entity_m: Option<Entity> = if picking_ui_node.is_picking {
-        window.cursor_position().and_then(|cursor_position| {
-            let mut nodes_under_cursor = Vec::new();
-
-            for (entity, global_transform, node) in node_query.iter() {
-                let rect =
-                    Rect::from_center_size(global_transform.translation().truncate(), node.size());
-                let scale: f32 = window.scale_factor() / ui_scale.0;
-                let position = Rect {
-                    min: Vec2::new(rect.min.x / scale, rect.min.y / scale),
-                    max: Vec2::new(rect.max.x / scale, rect.max.y / scale),
-                };
-
-                dbg!(scale, position, node.size());
-                if position.contains(cursor_position) {
-                    let z = node.stack_index();
-
-                    nodes_under_cursor.push((entity, z));
-                }
-            }
-
-            nodes_under_cursor.sort_by(|a, b| b.1.cmp(&a.1));
-
-            nodes_under_cursor.first().map(|(top_entity, _)| {
-                if mouse_button_input.just_pressed(MouseButton::Left) {
-                    previous_resource.selected = Some(*top_entity);
-                    style_under_inspection.entity = Some(*top_entity);
-                    previous_resource.hovered = None;
-                }
-                *top_entity
+    // The inspector window sits on top of the app, so the pointer belongs to egui while over it.
+    let pointer_over_egui = pointer_over_egui(&mut contexts);
+    picking_ui_node.hovered = None;
+    let entity_m: Option<Entity> = if picking_ui_node.is_picking && pointer_over_egui {
+        None
+    } else if picking_ui_node.is_picking {
+        let top_entity = if pointer_pick.enabled {
+            pointer_pick.hovered
+        } else {
+            window.cursor_position().and_then(|cursor_position| {
+                pick_index.top_node_at(window, &node_query, cursor_position)
             })
-        })
+        };
+        let clicked = mouse_button_input.just_pressed(MouseButton::Left);
+        if let Some(clicked_entity) = top_entity.filter(|_| clicked) {
+            previous_resource.selected = Some(clicked_entity);
+            style_under_inspection.entity = Some(clicked_entity);
+            previous_resource.hovered = None;
+        }
+        top_entity
     } else {
         previous_resource.hovered.or(previous_resource.selected)
     };
+    let mut highlighted = false;
     if let Some(entity) = entity_m {
-        if let Ok((node, tf)) = node_q.get(entity) {
-            let rect = Rect::from_center_size(tf.translation().truncate(), node.size());
-            let scale: f32 = window.scale_factor() / ui_scale.0;
-            let pos = Rect {
-                min: Vec2::new(rect.min.x / scale, rect.min.y / scale),
-                max: Vec2::new(rect.max.x / scale, rect.max.y / scale),
-            };
+        if let Ok((style, node, tf, parent)) = node_q.get(entity) {
+            highlighted = true;
+            let pos = node_window_rect(window, node, tf);
             let left = window.width() / -2.0;
 
             let top = window.height() / 2.0;
@@ -204,48 +334,204 @@ fn ui_node_hit_test_system(
                 Color::srgba(0.0, 0.0, 1.0, 0.3),
             );
 
-            show_hovered_ui(&mut commands, &hovered_ui_wrapper_q, &hovered_ui_q, pos)
+            if picking_ui_node.is_picking {
+                picking_ui_node.hovered = Some((entity, pos));
+            }
+
+            let box_model = BoxModel::new(
+                style,
+                node,
+                tf,
+                parent.and_then(|parent| parent_node_q.get(parent.get()).ok()),
+                window.physical_size().as_vec2() * node.inverse_scale_factor(),
+            );
+            if let Some(ctx) = contexts.try_ctx_mut() {
+                box_model::paint_box_model_labels(ctx, &box_model, ui_scale.0);
+            }
+
+            let region = style_under_inspection
+                .region
+                .filter(|_| !picking_ui_node.is_picking && previous_resource.selected == entity_m);
+            show_hovered_ui(&mut hovered_ui_q, &box_model, region);
         }
     }
-    if mouse_button_input.just_pressed(MouseButton::Left) {
+    let visibility = if highlighted {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    for mut wrapper_visibility in &mut hovered_ui_wrapper_q {
+        wrapper_visibility.set_if_neq(visibility);
+    }
+    if mouse_button_input.just_pressed(MouseButton::Left) && !pointer_over_egui {
         picking_ui_node.is_picking = false;
     }
 }
 
-fn create_ui(
+type TooltipNodeData = (
+    Option<&'static Name>,
+    &'static Node,
+    &'static ComputedNode,
+    Has<Text>,
+    Has<Button>,
+    Has<ImageNode>,
+);
+
+fn show_picking_tooltip(
     mut contexts: EguiContexts,
-    ui_root_q: Query<
-        (Entity, Option<&Children>),
+    picking_ui_node: Res<PickingUiNode>,
+    node_q: Query<TooltipNodeData>,
+) {
+    let Some((entity, pos)) = picking_ui_node.hovered else {
+        return;
+    };
+    let Ok((name, style, node, has_text, has_button, has_image)) = node_q.get(entity) else {
+        return;
+    };
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+    let title = match name {
+        Some(n) => format!("{} ({})", n.as_str(), entity),
+        None => format!("{}", entity),
+    };
+    let size = node.size() * node.inverse_scale_factor();
+    let components: Vec<&str> = [
+        (has_text, "Text"),
+        (has_button, "Button"),
+        (has_image, "ImageNode"),
+    ]
+    .into_iter()
+    .filter_map(|(has, label)| has.then_some(label))
+    .collect();
+
+    // Prefer the space below the node like browser devtools do, fall back to above it near the
+    // bottom of the window. `constrain` keeps the label inside the window horizontally.
+    let (anchor, pivot) = if pos.max.y + 80.0 < ctx.screen_rect().height() {
         (
-            Without<Parent>,
-            Without<HoverUiElementWrapperMarker>,
-            With<Node>,
-        ),
-    >,
+            egui::pos2(pos.min.x, pos.max.y + 6.0),
+            egui::Align2::LEFT_TOP,
+        )
+    } else {
+        (
+            egui::pos2(pos.min.x, pos.min.y - 6.0),
+            egui::Align2::LEFT_BOTTOM,
+        )
+    };
+    egui::Area::new(egui::Id::new("ui_inspector_picking_tooltip"))
+        .order(egui::Order::Tooltip)
+        .fixed_pos(anchor)
+        .pivot(pivot)
+        .constrain(true)
+        .interactable(false)
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.strong(title);
+                ui.label(format!("{:.1} × {:.1}", size.x, size.y));
+                if !components.is_empty() {
+                    ui.label(components.join(", "));
+                }
+                ui.label(format!(
+                    "display: {:?}, position: {:?}",
+                    style.display, style.position_type
+                ));
+            });
+        });
+}
+
+// While picking, a transparent node covers the whole window so the click that picks a node
+// never reaches the app's own `Interaction` or picking observers. It is kept until the mouse
+// button is released, otherwise the release would still land on whatever is underneath.
+fn block_app_input_while_picking(
+    picking_ui_node: Res<PickingUiNode>,
+    mouse_button_input: Res<ButtonInput<MouseButton>>,
+    blocker_q: Query<Entity, With<PickingInputBlockerMarker>>,
+    mut pointer_pick: ResMut<pointer_pick::PointerPick>,
+    mut picking_plugin: Option<ResMut<PickingPlugin>>,
+    mut commands: Commands,
+) {
+    if picking_ui_node.is_picking {
+        if blocker_q.is_empty() {
+            let mut blocker = commands.spawn((
+                Node {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(0.0),
+                    top: Val::Px(0.0),
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                FocusPolicy::Block,
+                Interaction::None,
+                GlobalZIndex(i32::MAX),
+                PickingInputBlockerMarker,
+                InspectorOverlayMarker,
+                Name::new("Picking input blocker"),
+            ));
+            // The backends have to see the app's nodes under the blocker to report them, so
+            // `bevy_picking`'s events are kept from the app by pausing its focus instead.
+            if pointer_pick.enabled {
+                blocker.insert(PickingBehavior::IGNORE);
+                if let Some(picking_plugin) = picking_plugin.as_deref_mut() {
+                    pointer_pick.pause_focus(picking_plugin);
+                }
+            }
+        }
+    } else if !mouse_button_input.pressed(MouseButton::Left)
+        && !mouse_button_input.just_released(MouseButton::Left)
+    {
+        // Waiting a frame past the release keeps it from reaching `bevy_picking` once its
+        // focus runs again.
+        for entity in &blocker_q {
+            commands.entity(entity).despawn_recursive();
+        }
+        if let Some(picking_plugin) = picking_plugin.as_deref_mut() {
+            pointer_pick.resume_focus(picking_plugin);
+        }
+    }
+}
+
+fn create_ui(
+    mut contexts: EguiContexts,
     parents_q: Query<&Parent, With<Node>>,
-    ui_q: Query<(Entity, Option<&Children>, Option<&Name>), (With<Node>)>,
+    ui_q: tree_row::TreeNodes,
     mut style_q: Query<(&mut Node, &mut BorderColor, &mut BackgroundColor)>,
     mut previous_resource: ResMut<RestorePreviousResource>,
     mut style_under_inspection: ResMut<ActiveStyleInspection>,
     mut selected_node: Local<Option<Entity>>,
-    mut collapse_all: Local<Option<bool>>,
     mut picking_ui_node: ResMut<PickingUiNode>,
+    mut rulers: ResMut<rulers::Rulers>,
+    mut overlay_settings: ResMut<OverlaySettings>,
+    mut search: ResMut<search::TreeSearch>,
+    mut tree_edits: ResMut<hierarchy_edit::TreeEdits>,
+    mut ignored: ResMut<ignore::IgnoredNodes>,
+    mut tree_model: ResMut<tree_model::TreeModel>,
+    mut hidden_nodes: ResMut<hide::HiddenNodes>,
 ) {
-    // Used to open or close Node hierarchy when picking a UI node
-    let mut open_on_change: Option<Entity> = None;
-    if *selected_node != previous_resource.selected {
-        *selected_node = previous_resource.selected;
-        open_on_change = *selected_node;
-    }
-
-    let mut parents_of_selected = Vec::new();
+    style_under_inspection.region = None;
+    tree_edits.drop_target = None;
+    // The selected node and its ancestors, from the root down.
+    let mut path_of_selected = Vec::new();
     if let Some(selected) = previous_resource.selected {
-        parents_of_selected.push(selected);
+        path_of_selected.push(selected);
         let mut current_entity = selected;
         while let Ok(parent) = parents_q.get(current_entity) {
-            parents_of_selected.push(parent.get());
+            path_of_selected.push(parent.get());
             current_entity = parent.get();
         }
+        path_of_selected.reverse();
+    }
+    // Open the hierarchy down to a newly selected node, e.g. when picking one
+    if *selected_node != previous_resource.selected {
+        *selected_node = previous_resource.selected;
+        if let Some(selected) = previous_resource.selected {
+            tree_model.expand(path_of_selected.iter().copied());
+            tree_model.scroll_to(selected);
+        }
+    }
+    // New search results open the branches leading to a match and close the rest.
+    if search.results_changed && search.is_active() {
+        tree_model.expand_only(search.ancestors.iter().copied());
     }
     let mut something_hovered = false;
     egui::Window::new("UI Inspector").show(contexts.ctx_mut(), |ui| {
@@ -253,15 +539,45 @@ fn create_ui(
             if ui.button("pick element").clicked() {
                 picking_ui_node.is_picking = true;
             }
-            let collapse = ui.button("collapse all");
-            let expand = ui.button("expand all");
-
-            if collapse.clicked() {
-                *collapse_all = Some(false);
-            } else if expand.clicked() {
-                *collapse_all = Some(true);
-            } else {
-                *collapse_all = None;
+            if ui.button("collapse all").clicked() {
+                tree_model.collapse_all();
+            }
+            if ui.button("expand all").clicked() {
+                tree_model.expand_all();
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("overlays:");
+            ui.checkbox(&mut rulers.enabled, "rulers");
+            ui.checkbox(&mut overlay_settings.layout, "layout");
+            ui.checkbox(&mut overlay_settings.wireframe, "wireframe");
+            ui.add_enabled(
+                overlay_settings.wireframe,
+                egui::Checkbox::new(&mut overlay_settings.wireframe_names, "names"),
+            );
+            ui.checkbox(&mut overlay_settings.overflow, "overflow");
+            ui.checkbox(&mut overlay_settings.problems, "problems");
+        });
+        // Written only on change, as picking rebuilds its index whenever this changes.
+        let mut show_ignored = ignored.show;
+        ui.checkbox(&mut show_ignored, "show ignored");
+        if show_ignored != ignored.show {
+            ignored.show = show_ignored;
+        }
+        ui.horizontal(|ui| {
+            ui.label("hide with:");
+            ui.radio_value(&mut hidden_nodes.mode, HideMode::Visibility, "visibility");
+            ui.radio_value(&mut hidden_nodes.mode, HideMode::Display, "display");
+            if let Some(isolated) = hidden_nodes.isolated {
+                if ui
+                    .button("stop isolating")
+                    .on_hover_text(isolated.to_string())
+                    .clicked()
+                {
+                    tree_edits
+                        .pending
+                        .push(hierarchy_edit::TreeEdit::Isolate(None));
+                }
             }
         });
 
@@ -269,38 +585,61 @@ fn create_ui(
             ui.vertical(|ui| {
                 ui.set_width(200.0);
                 ui.set_height(600.0);
-                ui_root_q
-                    .iter()
-                    .enumerate()
-                    .for_each(|(i, (root_e, children))| {
-                        ui.label("Root");
-                        egui::ScrollArea::vertical()
-                            .id_salt(i.to_string())
-                            .auto_shrink([false, true]) // Ensure the ScrollArea doesn't shrink automatically
-                            .show(ui, |ui| {
-                                let button = ui.button("Select");
-                                if button.clicked() {
-                                    previous_resource.selected = Some(root_e);
-                                    style_under_inspection.entity = Some(root_e);
-                                }
-
-                                let something_within_root_hoverd = render_nested_elements(
-                                    ui,
-                                    &ui_q,
-                                    children.into(),
-                                    &mut previous_resource,
-                                    &mut style_under_inspection,
-                                    &parents_of_selected,
-                                    &open_on_change,
-                                    &collapse_all,
-                                );
-                                if something_within_root_hoverd {
-                                    something_hovered = true;
-                                }
-                            });
-                    });
+                ui.horizontal(|ui| {
+                    let response = ui.add(
+                        egui::TextEdit::singleline(&mut search.query)
+                            .hint_text("search")
+                            .desired_width(120.0),
+                    );
+                    // Enter and Shift+Enter step through the results without leaving the box.
+                    if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                        let backwards = ui.input(|i| i.modifiers.shift);
+                        if let Some(entity) = search.step(backwards) {
+                            previous_resource.selected = Some(entity);
+                            style_under_inspection.entity = Some(entity);
+                        }
+                        response.request_focus();
+                    }
+                    if search.is_active() {
+                        let current = search.current.map_or(0, |current| current + 1);
+                        ui.label(format!("{current}/{}", search.matches.len()));
+                    }
+                });
+                something_hovered = tree_model::show_tree(
+                    ui,
+                    &mut tree_model,
+                    &ui_q,
+                    &mut previous_resource,
+                    &mut style_under_inspection,
+                    &search,
+                    &mut tree_edits,
+                    &hidden_nodes,
+                );
             });
             ui.vertical(|ui| {
+                ui.horizontal_wrapped(|ui| {
+                    ui.spacing_mut().item_spacing.x = 2.0;
+                    for (i, entity) in path_of_selected.iter().enumerate() {
+                        let Ok(item) = ui_q.get(*entity) else {
+                            continue;
+                        };
+                        if i > 0 {
+                            ui.weak("›");
+                        }
+                        let is_selected = previous_resource.selected == Some(*entity);
+                        let crumb = ui
+                            .selectable_label(is_selected, tree_row::node_title(&item))
+                            .on_hover_text(entity.to_string());
+                        if crumb.clicked() {
+                            previous_resource.selected = Some(*entity);
+                            style_under_inspection.entity = Some(*entity);
+                        }
+                        if crumb.hovered() {
+                            previous_resource.hovered = Some(*entity);
+                            something_hovered = true;
+                        }
+                    }
+                });
                 if let Some((mut selected_style, _, _)) = previous_resource
                     .selected
                     .and_then(|selected_e| style_q.get_mut(selected_e).ok())
@@ -323,27 +662,27 @@ fn create_ui(
                         val_input(ui, &mut selected_style.max_height, "max-height");
                     });
 
-                    ui.horizontal(|ui| {
-                        ui.label("padding:");
-                        val_input(ui, &mut selected_style.padding.left, "padding.left");
-                        val_input(ui, &mut selected_style.padding.bottom, "padding.bottom");
-                        val_input(ui, &mut selected_style.padding.right, "padding.right");
-                        val_input(ui, &mut selected_style.padding.top, "padding.top");
-                    });
-                    ui.horizontal(|ui| {
-                        ui.label("margin:");
-                        val_input(ui, &mut selected_style.margin.left, "margin.left");
-                        val_input(ui, &mut selected_style.margin.bottom, "margin.bottom");
-                        val_input(ui, &mut selected_style.margin.right, "margin.right");
-                        val_input(ui, &mut selected_style.margin.top, "margin.top");
-                    });
-                    ui.horizontal(|ui| {
-                        ui.label("border:");
-                        val_input(ui, &mut selected_style.border.left, "border.left");
-                        val_input(ui, &mut selected_style.border.bottom, "border.bottom");
-                        val_input(ui, &mut selected_style.border.right, "border.right");
-                        val_input(ui, &mut selected_style.border.top, "border.top");
-                    });
+                    for (label, layer) in [
+                        ("padding", HighlightLayer::Padding),
+                        ("margin", HighlightLayer::Margin),
+                        ("border", HighlightLayer::Border),
+                    ] {
+                        let row = ui.horizontal(|ui| {
+                            ui.label(format!("{label}:"));
+                            let sides = match layer {
+                                HighlightLayer::Padding => &mut selected_style.padding,
+                                HighlightLayer::Margin => &mut selected_style.margin,
+                                _ => &mut selected_style.border,
+                            };
+                            sides_input(ui, sides, label)
+                        });
+                        if row.response.contains_pointer() {
+                            style_under_inspection.region = Some(HighlightRegion {
+                                layer,
+                                side: row.inner,
+                            });
+                        }
+                    }
                     ui.horizontal(|ui| {
                         ui.label("pos:");
                         val_input(ui, &mut selected_style.left, "left");
@@ -443,159 +782,382 @@ fn create_ui(
             });
         });
     });
+    hierarchy_edit::finish_drag(contexts.ctx_mut(), &mut tree_edits);
     if !something_hovered {
         previous_resource.hovered = None;
     }
 }
 
-fn render_nested_elements(
-    ui: &mut Ui,
-    ui_q: &Query<(Entity, Option<&Children>, Option<&Name>), With<Node>>,
-    children: Option<&Children>,
-    previous_resource: &mut ResMut<RestorePreviousResource>,
-    style_under_inspection: &mut ResMut<ActiveStyleInspection>,
-    parents_of_selected: &Vec<Entity>,
-    open_on_change: &Option<Entity>,
-    collapse_all: &Option<bool>,
-) -> bool {
-    let mut something_hovered = false;
-    if let Some(children) = children {
-        children.iter().for_each(|child| {
-            if let Ok((e, children, name)) = ui_q.get(*child) {
-                let name = match name {
-                    Some(n) => format!("{} ({})", n.as_str(), e),
-                    None => format!("{}", e),
-                };
-                let is_selected = previous_resource.selected == Some(*child);
-
-                let style = ui.style().clone();
-
-                ui.set_style(style);
-                let mut label = egui::RichText::new(name);
-                if is_selected {
-                    label = label
-                        .color(egui::Color32::from_rgb(255, 255, 255))
-                        .background_color(egui::Color32::from_rgb(100, 149, 237));
-                }
-                let thing = egui::CollapsingHeader::new(label)
-                    .open(
-                        collapse_all
-                            .or(open_on_change.map(|_| parents_of_selected.contains(child))),
-                    )
-                    .show(ui, |ui| {
-                        let button = ui.button("Select");
-                        if button.clicked() {
-                            previous_resource.selected = Some(*child);
-                            style_under_inspection.entity = Some(*child);
-                        }
+#[derive(Component)]
+struct HoverUiElementWrapperMarker;
+// Which area of the box model a node of the highlight overlay outlines.
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+enum HighlightLayer {
+    Margin,
+    Border,
+    Padding,
+    Content,
+}
+impl HighlightLayer {
+    fn color(self) -> Color {
+        match self {
+            HighlightLayer::Margin => box_model::MARGIN_COLOR,
+            HighlightLayer::Border => box_model::BORDER_COLOR,
+            HighlightLayer::Padding => box_model::PADDING_COLOR,
+            HighlightLayer::Content => box_model::CONTENT_COLOR,
+        }
+    }
+}
 
-                        let something_already_hovered = render_nested_elements(
-                            ui,
-                            ui_q,
-                            children,
-                            previous_resource,
-                            style_under_inspection,
-                            parents_of_selected,
-                            open_on_change,
-                            collapse_all,
-                        );
-                        if something_already_hovered {
-                            something_hovered = true;
-                        }
-                    });
-                if thing.header_response.hovered() {
-                    previous_resource.hovered = Some(*child);
-                    something_hovered = true;
-                }
-            }
-        });
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Side {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+impl Side {
+    fn only(self, sides: &BorderRect) -> BorderRect {
+        let mut only = BorderRect::ZERO;
+        match self {
+            Side::Left => only.left = sides.left,
+            Side::Right => only.right = sides.right,
+            Side::Top => only.top = sides.top,
+            Side::Bottom => only.bottom = sides.bottom,
+        }
+        only
     }
-    something_hovered
+}
+
+// The part of the selected node whose property is hovered in the inspector, which is then
+// highlighted on its own.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct HighlightRegion {
+    layer: HighlightLayer,
+    side: Option<Side>,
 }
 #[derive(Component)]
-struct HoverUiElementWrapperMarker;
+struct PickingInputBlockerMarker;
+// Every entity the inspector spawns for itself, which is left out of picking and the hierarchy.
 #[derive(Component)]
-struct HoverUiElementMarker;
+struct InspectorOverlayMarker;
+
+// The highlight is drawn on top of everything, but must not take pointer input from the app.
+fn overlay_node(node: Node) -> impl Bundle {
+    (node, PickingBehavior::IGNORE, InspectorOverlayMarker)
+}
+
+type HighlightLayers<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut Node,
+        &'static mut BorderColor,
+        &'static mut BackgroundColor,
+        &'static HighlightLayer,
+    ),
+    With<InspectorOverlayMarker>,
+>;
 
 fn show_hovered_ui(
-    commands: &mut Commands,
-    hovered_ui_wrapper_q: &Query<Entity, With<HoverUiElementWrapperMarker>>,
-    hovered_ui_q: &Query<(Entity, &Node), With<HoverUiElementMarker>>,
-    pos: Rect,
+    hovered_ui_q: &mut HighlightLayers,
+    box_model: &BoxModel,
+    region: Option<HighlightRegion>,
 ) {
-    let new_style = Node { ..default() };
-
-    if let Ok((e, old_style)) = hovered_ui_q.get_single() {
-        if old_style.left == new_style.left
-            && old_style.top == new_style.top
-            && old_style.width == new_style.width
-            && old_style.height == new_style.height
-        {
-            return;
+    let margin_box = box_model.margin_box();
+    // While a single side is edited, only that side of its area is drawn.
+    let sides = |layer: HighlightLayer, sides: &BorderRect| match region {
+        Some(HighlightRegion {
+            layer: region_layer,
+            side: Some(side),
+        }) if region_layer == layer => sides_to_ui_rect(&side.only(sides)),
+        _ => sides_to_ui_rect(sides),
+    };
+    for (mut style, mut border_color, mut background_color, layer) in hovered_ui_q {
+        // Only write when something moved, so the overlay doesn't trigger a relayout every frame.
+        let mut new_style = style.clone();
+        match layer {
+            HighlightLayer::Margin => {
+                new_style.left = Val::Px(margin_box.min.x);
+                new_style.top = Val::Px(margin_box.min.y);
+                new_style.width = Val::Px(margin_box.width());
+                new_style.height = Val::Px(margin_box.height());
+                new_style.border = sides(*layer, &box_model.margin);
+            }
+            HighlightLayer::Border => new_style.border = sides(*layer, &box_model.border),
+            HighlightLayer::Padding => new_style.border = sides(*layer, &box_model.padding),
+            HighlightLayer::Content => {}
         }
-    }
+        style.set_if_neq(new_style);
 
-    for entity in hovered_ui_wrapper_q {
-        commands.entity(entity).despawn_recursive();
+        let color = if region.is_none_or(|region| region.layer == *layer) {
+            layer.color()
+        } else {
+            Color::NONE
+        };
+        if *layer == HighlightLayer::Content {
+            background_color.set_if_neq(BackgroundColor(color));
+        } else {
+            border_color.set_if_neq(BorderColor(color));
+        }
     }
+}
 
+// The highlight is spawned once, hidden, and moved onto whatever is hovered or selected.
+fn spawn_highlight_overlay(
+    mut commands: Commands,
+    hovered_ui_wrapper_q: Query<(), With<HoverUiElementWrapperMarker>>,
+) {
+    if !hovered_ui_wrapper_q.is_empty() {
+        return;
+    }
     commands
         .spawn((
-            Node {
+            overlay_node(Node {
                 position_type: PositionType::Absolute,
                 left: Val::Px(0.0),
                 top: Val::Px(0.0),
                 width: Val::Percent(100.0),
                 height: Val::Percent(100.0),
                 ..default()
-            },
+            }),
             GlobalZIndex(i32::MAX),
+            Visibility::Hidden,
             HoverUiElementWrapperMarker,
+            Name::new("Highlight overlay"),
         ))
         .with_children(|builder| {
-            builder.spawn((
-                Node {
-                    left: Val::Px(pos.min.x),
-                    top: Val::Px(pos.min.y),
-                    width: Val::Px(pos.max.x - pos.min.x),
-                    height: Val::Px(pos.max.y - pos.min.y),
-                    ..default()
-                },
-                BackgroundColor(Color::srgba(0.0, 0.0, 1.0, 0.3)),
-                HoverUiElementMarker,
-            ));
+            // Each area is drawn as the border of a node nested in the previous area, so the
+            // padding box of one layer is exactly the border box of the next.
+            builder
+                .spawn((
+                    overlay_node(Node::default()),
+                    BorderColor(box_model::MARGIN_COLOR),
+                    BackgroundColor::default(),
+                    HighlightLayer::Margin,
+                ))
+                .with_children(|builder| {
+                    builder
+                        .spawn((
+                            overlay_node(Node {
+                                width: Val::Percent(100.0),
+                                height: Val::Percent(100.0),
+                                ..default()
+                            }),
+                            BorderColor(box_model::BORDER_COLOR),
+                            BackgroundColor::default(),
+                            HighlightLayer::Border,
+                        ))
+                        .with_children(|builder| {
+                            builder
+                                .spawn((
+                                    overlay_node(Node {
+                                        width: Val::Percent(100.0),
+                                        height: Val::Percent(100.0),
+                                        ..default()
+                                    }),
+                                    BorderColor(box_model::PADDING_COLOR),
+                                    BackgroundColor::default(),
+                                    HighlightLayer::Padding,
+                                ))
+                                .with_children(|builder| {
+                                    builder.spawn((
+                                        overlay_node(Node {
+                                            width: Val::Percent(100.0),
+                                            height: Val::Percent(100.0),
+                                            ..default()
+                                        }),
+                                        BorderColor::default(),
+                                        BackgroundColor(box_model::CONTENT_COLOR),
+                                        HighlightLayer::Content,
+                                    ));
+                                });
+                        });
+                });
         });
 }
 
-fn setup(mut commands: Commands) {
+fn sides_to_ui_rect(sides: &BorderRect) -> UiRect {
+    // Negative margins can't be drawn as a border.
+    UiRect::new(
+        Val::Px(sides.left.max(0.0)),
+        Val::Px(sides.right.max(0.0)),
+        Val::Px(sides.top.max(0.0)),
+        Val::Px(sides.bottom.max(0.0)),
+    )
+}
+
+fn setup(mut commands: Commands, settings: Res<UiInspectorSettings>) {
+    if !settings.spawn_camera {
+        return;
+    }
     commands.spawn((
         Camera2d,
         Camera {
             clear_color: ClearColorConfig::None,
-            order: 4,
+            order: settings.camera_order,
             ..default()
         },
-        RenderLayers::layer(10),
+        RenderLayers::layer(settings.render_layer),
+        InspectorOverlayMarker,
         Name::new("Plugin camera"),
     ));
 }
-pub struct UiInspectorPlugin;
+
+/// Where the inspector draws its overlay.
+#[derive(Resource, Clone, Debug)]
+pub struct UiInspectorSettings {
+    /// Render layer of the overlay camera and the inspector's gizmos.
+    pub render_layer: usize,
+    /// Order of the overlay camera, it should render after the app's cameras.
+    pub camera_order: isize,
+    /// When `false` no overlay camera is spawned, and a camera of the app that renders
+    /// `render_layer` is used instead.
+    pub spawn_camera: bool,
+    /// Hover and select nodes through `bevy_picking` instead of the inspector's own hit test.
+    /// Falls back to the hit test when the UI picking backend isn't added.
+    pub bevy_picking: bool,
+    /// How the eye icons in the hierarchy hide nodes, can be changed in the inspector.
+    pub hide_mode: HideMode,
+}
+impl Default for UiInspectorSettings {
+    fn default() -> Self {
+        Self {
+            render_layer: 10,
+            camera_order: 4,
+            spawn_camera: true,
+            bevy_picking: false,
+            hide_mode: HideMode::default(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct UiInspectorPlugin {
+    settings: UiInspectorSettings,
+    ignore_filter: Option<ignore::IgnoreFilter>,
+}
+impl UiInspectorPlugin {
+    pub fn with_render_layer(mut self, render_layer: usize) -> Self {
+        self.settings.render_layer = render_layer;
+        self
+    }
+    pub fn with_camera_order(mut self, camera_order: isize) -> Self {
+        self.settings.camera_order = camera_order;
+        self
+    }
+    /// Draw the overlay with a camera of the app that renders the inspector's render layer.
+    pub fn with_existing_camera(mut self) -> Self {
+        self.settings.spawn_camera = false;
+        self
+    }
+    /// Pick nodes with `bevy_picking`, which accounts for clipping and multiple cameras.
+    ///
+    /// While picking, `PickingPlugin::is_focus_enabled` is turned off so the click doesn't reach
+    /// the app's `Pointer` observers. Nodes the app had hovered stay hovered until it ends.
+    pub fn with_bevy_picking(mut self) -> Self {
+        self.settings.bevy_picking = true;
+        self
+    }
+    /// How the eye icons in the hierarchy hide nodes in the app, `Visibility::Hidden` by default.
+    pub fn with_hide_mode(mut self, hide_mode: HideMode) -> Self {
+        self.settings.hide_mode = hide_mode;
+        self
+    }
+    /// Leave out the nodes the filter returns `true` for, with everything below them, like
+    /// nodes with [`UiInspectorIgnore`]. The filter runs again when nodes are added, removed
+    /// or moved, not when other components change.
+    pub fn with_ignore_filter(
+        mut self,
+        filter: impl Fn(EntityRef) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.ignore_filter = Some(std::sync::Arc::new(filter));
+        self
+    }
+}
 impl Plugin for UiInspectorPlugin {
     fn build(&self, app: &mut App) {
         if !app.is_plugin_added::<EguiPlugin>() {
             app.add_plugins(EguiPlugin);
         }
+        app.insert_resource(self.settings.clone());
         app.insert_gizmo_config(
-            DefaultGizmoConfigGroup,
+            UiInspectorGizmos,
             GizmoConfig {
-                render_layers: RenderLayers::layer(10),
+                render_layers: RenderLayers::layer(self.settings.render_layer),
+                line_width: 1.0,
+                depth_bias: -1.0,
                 ..default()
             },
         );
         app.insert_resource(RestorePreviousResource::default());
         app.insert_resource(ActiveStyleInspection::default());
         app.insert_resource(PickingUiNode::default());
-        app.add_systems(Update, (create_ui, ui_node_hit_test_system));
+        app.insert_resource(rulers::Rulers::default());
+        app.insert_resource(OverlaySettings::default());
+        app.init_resource::<pick_index::PickIndex>();
+        app.init_resource::<pointer_pick::PointerPick>();
+        app.init_resource::<search::TreeSearch>();
+        app.init_resource::<hierarchy_edit::TreeEdits>();
+        app.init_resource::<ignore::IgnoredNodes>();
+        app.init_resource::<tree_model::TreeModel>();
+        app.insert_resource(hide::HiddenNodes::new(self.settings.hide_mode));
+        app.insert_resource(ignore::IgnoreSettings {
+            filter: self.ignore_filter.clone(),
+        });
+        app.register_type::<UiInspectorIgnore>();
+        app.add_systems(
+            Update,
+            (
+                ignore::update_ignored_nodes
+                    .run_if(ignore::ignored_nodes_outdated)
+                    .before(search::update_search_matches),
+                search::update_search_matches
+                    .after(tree_model::update_tree_model)
+                    .before(create_ui),
+                tree_model::update_tree_model
+                    .after(ignore::update_ignored_nodes)
+                    .before(create_ui),
+                create_ui,
+                spawn_highlight_overlay.before(ui_node_hit_test_system),
+                pointer_pick::read_pointer_hits
+                    .after(ignore::update_ignored_nodes)
+                    .before(ui_node_hit_test_system),
+                ui_node_hit_test_system,
+                block_app_input_while_picking.after(ui_node_hit_test_system),
+                show_picking_tooltip.after(ui_node_hit_test_system),
+                measure::measure_distance_system.after(create_ui),
+                rulers::draw_rulers.after(create_ui),
+                layout_overlay::draw_layout_overlay.after(create_ui),
+                wireframe::draw_wireframe.after(create_ui),
+                overflow::show_overflow.after(create_ui),
+                lint::show_problems.after(create_ui),
+                hierarchy_edit::apply_tree_edits
+                    .run_if(|edits: Res<hierarchy_edit::TreeEdits>| !edits.pending.is_empty())
+                    .after(create_ui),
+            ),
+        );
+        app.add_systems(
+            PostUpdate,
+            pick_index::invalidate_pick_index
+                .after(bevy::ui::UiSystem::Stack)
+                .after(bevy::ui::UiSystem::Layout)
+                .after(bevy::transform::TransformSystem::TransformPropagate),
+        );
         app.add_systems(Startup, (setup));
     }
+    fn finish(&self, app: &mut App) {
+        if !self.settings.bevy_picking {
+            return;
+        }
+        // Other plugins may be added after this one, so this can only be checked once all are.
+        let enabled = app.is_plugin_added::<bevy::ui::picking_backend::UiPickingPlugin>()
+            && app.is_plugin_added::<bevy::picking::InteractionPlugin>();
+        if !enabled {
+            warn!("bevy_ui_inspector: the UI picking backend isn't added, falling back to the inspector's own picking");
+        }
+        app.world_mut()
+            .resource_mut::<pointer_pick::PointerPick>()
+            .enabled = enabled;
+    }
 }
@@ -0,0 +1,289 @@
+use bevy::{prelude::*, utils::HashSet};
+use bevy_egui::{egui, EguiContexts};
+
+use crate::{
+    format_px, node_window_rect, ActiveStyleInspection, InspectorOverlayMarker, OverlaySettings,
+    RestorePreviousResource, UiRoots,
+};
+
+struct Problem {
+    entity: Entity,
+    message: String,
+}
+
+type LintNodeData = (
+    &'static Node,
+    &'static ComputedNode,
+    &'static GlobalTransform,
+    Option<&'static Children>,
+    Option<&'static Name>,
+    Option<Ref<'static, Text>>,
+);
+
+type AppNodes = (With<Node>, Without<InspectorOverlayMarker>);
+
+// Whether the node's size along the axis comes from its content, which a percentage inside it
+// can't resolve against. Nodes stretched or grown by their container, or sized by their
+// insets, have a size of their own even with an auto width or height.
+fn content_sized(node: &Node, container: Option<&Node>, horizontal: bool) -> bool {
+    let (size, start, end) = if horizontal {
+        (node.width, node.left, node.right)
+    } else {
+        (node.height, node.top, node.bottom)
+    };
+    if size != Val::Auto {
+        return false;
+    }
+    if node.position_type == PositionType::Absolute {
+        return start == Val::Auto || end == Val::Auto;
+    }
+    let stretched = |container: &Node| match node.align_self {
+        AlignSelf::Auto => matches!(
+            container.align_items,
+            AlignItems::Default | AlignItems::Stretch
+        ),
+        align_self => align_self == AlignSelf::Stretch,
+    };
+    // Roots take the size of their content.
+    let Some(container) = container else {
+        return true;
+    };
+    match container.display {
+        Display::Flex => {
+            let row = matches!(
+                container.flex_direction,
+                FlexDirection::Row | FlexDirection::RowReverse
+            );
+            if row == horizontal {
+                node.flex_grow == 0.0 && node.flex_basis == Val::Auto
+            } else {
+                !stretched(container)
+            }
+        }
+        Display::Grid if horizontal => match node.justify_self {
+            JustifySelf::Auto => !matches!(
+                container.justify_items,
+                JustifyItems::Default | JustifyItems::Stretch
+            ),
+            justify_self => justify_self != JustifySelf::Stretch,
+        },
+        Display::Grid => !stretched(container),
+        Display::Block => !horizontal,
+        Display::None => false,
+    }
+}
+
+// Lists likely layout mistakes in the app's UI, each of which selects the node when clicked.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn show_problems(
+    windows: Query<&Window>,
+    settings: Res<OverlaySettings>,
+    root_q: UiRoots<Entity>,
+    orphan_q: Query<(Entity, &Parent), AppNodes>,
+    node_q: Query<LintNodeData, Without<InspectorOverlayMarker>>,
+    any_node_q: Query<(), With<Node>>,
+    mut previous_resource: ResMut<RestorePreviousResource>,
+    mut style_under_inspection: ResMut<ActiveStyleInspection>,
+    mut hidden_text_updates: Local<HashSet<Entity>>,
+    mut contexts: EguiContexts,
+) {
+    if !settings.problems {
+        return;
+    }
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let window_rect = Rect::from_corners(Vec2::ZERO, window.size());
+    let mut problems = Vec::new();
+
+    // Nodes whose parent isn't a node are laid out as roots, so they are walked as roots too.
+    // Each node is walked with its parent and its parent's container.
+    let mut stack: Vec<(Entity, Option<Entity>, Option<Entity>, bool)> = root_q
+        .iter()
+        .map(|root| (root, None, None, false))
+        .collect();
+    for (entity, parent) in orphan_q.iter() {
+        if !any_node_q.contains(parent.get()) {
+            problems.push(Problem {
+                entity,
+                message: format!(
+                    "parent {} has no Node, so this node is laid out as a root",
+                    parent.get()
+                ),
+            });
+            stack.push((entity, None, None, false));
+        }
+    }
+
+    let mut still_hidden_text_updates = HashSet::new();
+    while let Some((entity, parent, container, hidden_by_ancestor)) = stack.pop() {
+        let Ok((style, node, tf, children, _, text)) = node_q.get(entity) else {
+            continue;
+        };
+        let mut report = |message: String| problems.push(Problem { entity, message });
+        let hidden = hidden_by_ancestor || style.display == Display::None;
+
+        if let Some(text) = text {
+            let updated = text.is_changed() && !text.is_added();
+            if hidden_by_ancestor && (updated || hidden_text_updates.contains(&entity)) {
+                still_hidden_text_updates.insert(entity);
+                report("text is updated while an ancestor has display: none".to_string());
+            }
+        }
+
+        if !hidden {
+            let parent_data = parent.and_then(|parent| node_q.get(parent).ok());
+            let inverse = node.inverse_scale_factor();
+            let viewport = window.physical_size().as_vec2() * inverse;
+            let parent_size = parent_data
+                .as_ref()
+                .map(|(_, parent_node, ..)| {
+                    let inset = parent_node.content_inset();
+                    Vec2::new(
+                        parent_node.size().x - inset.left - inset.right,
+                        parent_node.size().y - inset.top - inset.bottom,
+                    ) * parent_node.inverse_scale_factor()
+                })
+                .unwrap_or(viewport);
+
+            if let Some((parent_style, ..)) = parent_data {
+                let container_style = container
+                    .and_then(|container| node_q.get(container).ok())
+                    .map(|(container_style, ..)| container_style);
+                for (val, horizontal, axis) in [
+                    (style.width, true, "width"),
+                    (style.height, false, "height"),
+                ] {
+                    if let Val::Percent(percent) = val {
+                        if content_sized(parent_style, container_style, horizontal) {
+                            report(format!(
+                                "{axis}: {percent}% inside a parent whose {axis} comes from its content"
+                            ));
+                        }
+                    }
+                }
+            }
+
+            for (min, max, parent_axis, axis) in [
+                (style.min_width, style.max_width, parent_size.x, "width"),
+                (style.min_height, style.max_height, parent_size.y, "height"),
+            ] {
+                if let (Ok(min), Ok(max)) = (
+                    min.resolve(parent_axis, viewport),
+                    max.resolve(parent_axis, viewport),
+                ) {
+                    if min > max {
+                        report(format!(
+                            "min-{axis} ({}) is larger than max-{axis} ({})",
+                            format_px(min),
+                            format_px(max)
+                        ));
+                    }
+                }
+            }
+
+            let has_children = children.is_some_and(|children| !children.is_empty());
+            if node.is_empty() && has_children {
+                let size = node.size() * inverse;
+                report(format!(
+                    "has children but a zero area ({} × {})",
+                    format_px(size.x),
+                    format_px(size.y)
+                ));
+            }
+
+            let rect = node_window_rect(window, node, tf);
+            if !node.is_empty() && rect.intersect(window_rect).is_empty() {
+                report("is positioned off-screen".to_string());
+            }
+
+            if style.position_type == PositionType::Absolute
+                && [style.left, style.right, style.top, style.bottom]
+                    .iter()
+                    .all(|inset| *inset == Val::Auto)
+            {
+                report("is absolutely positioned without left, right, top or bottom".to_string());
+            }
+        }
+
+        if let Some(children) = children {
+            stack.extend(
+                children
+                    .iter()
+                    .map(|child| (*child, Some(entity), parent, hidden)),
+            );
+        }
+    }
+    *hidden_text_updates = still_hidden_text_updates;
+
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+    egui::Window::new(format!("Problems ({})", problems.len()))
+        .id(egui::Id::new("ui_inspector_problems"))
+        .show(ctx, |ui| {
+            if problems.is_empty() {
+                ui.label("No problems found");
+                return;
+            }
+            egui::ScrollArea::vertical()
+                .max_height(300.0)
+                .show(ui, |ui| {
+                    for problem in problems {
+                        let name = node_q
+                            .get(problem.entity)
+                            .ok()
+                            .and_then(|(_, _, _, _, name, _)| name);
+                        let label = match name {
+                            Some(n) => {
+                                format!("{} ({}): {}", n.as_str(), problem.entity, problem.message)
+                            }
+                            None => format!("{}: {}", problem.entity, problem.message),
+                        };
+                        let is_selected = previous_resource.selected == Some(problem.entity);
+                        if ui.selectable_label(is_selected, label).clicked() {
+                            previous_resource.selected = Some(problem.entity);
+                            style_under_inspection.entity = Some(problem.entity);
+                        }
+                    }
+                });
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn auto_sized_nodes_can_get_their_size_from_the_container() {
+        let row = Node::default();
+        let column = Node {
+            flex_direction: FlexDirection::Column,
+            ..default()
+        };
+        let auto = Node::default();
+        // Stretched on the cross axis, sized by the content on the main axis.
+        assert!(content_sized(&auto, Some(&row), true));
+        assert!(!content_sized(&auto, Some(&row), false));
+        assert!(!content_sized(&auto, Some(&column), true));
+        assert!(content_sized(&auto, Some(&column), false));
+        assert!(content_sized(&auto, None, true));
+
+        let grown = Node {
+            flex_grow: 1.0,
+            align_self: AlignSelf::Center,
+            ..default()
+        };
+        assert!(!content_sized(&grown, Some(&row), true));
+        assert!(content_sized(&grown, Some(&row), false));
+
+        let inset = Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(0.0),
+            right: Val::Px(0.0),
+            ..default()
+        };
+        assert!(!content_sized(&inset, Some(&row), true));
+        assert!(content_sized(&inset, Some(&row), false));
+    }
+}
@@ -0,0 +1,141 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use crate::{
+    format_px, node_window_rect, paint_label, pick_index::PickIndex, pointer_over_egui,
+    window_to_gizmo, PickableNodes, PickingUiNode, RestorePreviousResource, UiInspectorGizmos,
+};
+
+const MEASURE_COLOR: Color = Color::srgb(1.0, 0.2, 0.4);
+
+// While Alt is held, draws the distances between the selected node and the node under the
+// cursor (or the hovered tree row).
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn measure_distance_system(
+    windows: Query<&Window>,
+    keys: Res<ButtonInput<KeyCode>>,
+    node_query: PickableNodes,
+    mut pick_index: ResMut<PickIndex>,
+    node_q: Query<(&ComputedNode, &GlobalTransform)>,
+    previous_resource: Res<RestorePreviousResource>,
+    picking_ui_node: Res<PickingUiNode>,
+    ui_scale: Res<UiScale>,
+    mut gizmos: Gizmos<UiInspectorGizmos>,
+    mut contexts: EguiContexts,
+) {
+    if picking_ui_node.is_picking || !keys.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]) {
+        return;
+    }
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let Some(selected) = previous_resource.selected else {
+        return;
+    };
+    let target = previous_resource.hovered.or_else(|| {
+        if pointer_over_egui(&mut contexts) {
+            return None;
+        }
+        window
+            .cursor_position()
+            .and_then(|cursor| pick_index.top_node_at(window, &node_query, cursor))
+    });
+    let Some(target) = target.filter(|target| *target != selected) else {
+        return;
+    };
+    let (Ok((selected_node, selected_tf)), Ok((target_node, target_tf))) =
+        (node_q.get(selected), node_q.get(target))
+    else {
+        return;
+    };
+    let selected_rect = node_window_rect(window, selected_node, selected_tf);
+    let target_rect = node_window_rect(window, target_node, target_tf);
+
+    gizmos.rect_2d(
+        window_to_gizmo(window, target_rect.center()),
+        target_rect.size(),
+        MEASURE_COLOR,
+    );
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+    for (start, end) in measurements(selected_rect, target_rect) {
+        gizmos.line_2d(
+            window_to_gizmo(window, start),
+            window_to_gizmo(window, end),
+            MEASURE_COLOR,
+        );
+        let middle = (start + end) / 2.0;
+        paint_label(
+            ctx,
+            "ui_inspector_measure",
+            egui::pos2(middle.x, middle.y),
+            format_px(start.distance(end) / ui_scale.0),
+            egui::Color32::from_rgb(255, 51, 102),
+        );
+    }
+}
+
+fn contains_rect(outer: Rect, inner: Rect) -> bool {
+    outer.min.cmple(inner.min).all() && inner.max.cmple(outer.max).all()
+}
+
+// Returns the lines to draw between two rects: the insets when one contains the other,
+// otherwise the gaps on the sides where they don't overlap.
+fn measurements(a: Rect, b: Rect) -> Vec<(Vec2, Vec2)> {
+    let mut lines = Vec::new();
+    let contained = if contains_rect(a, b) {
+        Some((a, b))
+    } else if contains_rect(b, a) {
+        Some((b, a))
+    } else {
+        None
+    };
+
+    if let Some((outer, inner)) = contained {
+        let center = inner.center();
+        lines.push((
+            Vec2::new(outer.min.x, center.y),
+            Vec2::new(inner.min.x, center.y),
+        ));
+        lines.push((
+            Vec2::new(inner.max.x, center.y),
+            Vec2::new(outer.max.x, center.y),
+        ));
+        lines.push((
+            Vec2::new(center.x, outer.min.y),
+            Vec2::new(center.x, inner.min.y),
+        ));
+        lines.push((
+            Vec2::new(center.x, inner.max.y),
+            Vec2::new(center.x, outer.max.y),
+        ));
+    } else {
+        // Measure along the middle of the overlapping span, or of the target when there is none.
+        let overlap = a.intersect(b);
+        let y = if overlap.min.y < overlap.max.y {
+            (overlap.min.y + overlap.max.y) / 2.0
+        } else {
+            b.center().y
+        };
+        let x = if overlap.min.x < overlap.max.x {
+            (overlap.min.x + overlap.max.x) / 2.0
+        } else {
+            b.center().x
+        };
+
+        if b.min.x >= a.max.x {
+            lines.push((Vec2::new(a.max.x, y), Vec2::new(b.min.x, y)));
+        } else if a.min.x >= b.max.x {
+            lines.push((Vec2::new(b.max.x, y), Vec2::new(a.min.x, y)));
+        }
+        if b.min.y >= a.max.y {
+            lines.push((Vec2::new(x, a.max.y), Vec2::new(x, b.min.y)));
+        } else if a.min.y >= b.max.y {
+            lines.push((Vec2::new(x, b.max.y), Vec2::new(x, a.min.y)));
+        }
+    }
+
+    lines.retain(|(start, end)| start.distance(*end) > 0.0);
+    lines
+}
@@ -0,0 +1,163 @@
+use bevy::{prelude::*, text::TextLayoutInfo, ui::CalculatedClip};
+use bevy_egui::{egui, EguiContexts};
+
+use crate::{
+    layout_overlay::hatch, node_window_rect, window_to_gizmo, ActiveStyleInspection,
+    InspectorOverlayMarker, OverlaySettings, RestorePreviousResource, UiInspectorGizmos,
+};
+
+const OVERFLOW_COLOR: Color = Color::srgb(1.0, 0.2, 0.2);
+const CLIPPED_COLOR: Color = Color::srgba(1.0, 0.2, 0.2, 0.5);
+const TEXT_COLOR: Color = Color::srgb(1.0, 0.8, 0.0);
+
+#[derive(Clone, Copy, PartialEq)]
+enum OverflowKind {
+    Children,
+    Clipped,
+    Text,
+}
+
+impl std::fmt::Display for OverflowKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OverflowKind::Children => f.write_str("children overflow"),
+            OverflowKind::Clipped => f.write_str("clipped"),
+            OverflowKind::Text => f.write_str("text overflows"),
+        }
+    }
+}
+
+type OverflowNodeData = (
+    Entity,
+    &'static ComputedNode,
+    &'static GlobalTransform,
+    Option<&'static Parent>,
+    Option<&'static Children>,
+    Option<&'static CalculatedClip>,
+    Option<&'static TextLayoutInfo>,
+    Option<&'static Name>,
+);
+
+fn contains_rect(outer: Rect, inner: Rect) -> bool {
+    // Allow for rounding of the layout.
+    let outer = outer.inflate(0.5);
+    outer.min.cmple(inner.min).all() && inner.max.cmple(outer.max).all()
+}
+
+// Parts of `rect` that fall outside of `clip`.
+fn clipped_parts(rect: Rect, clip: Rect) -> Vec<Rect> {
+    let visible = rect.intersect(clip);
+    if visible.is_empty() {
+        return vec![rect];
+    }
+    [
+        Rect::new(rect.min.x, rect.min.y, rect.max.x, visible.min.y),
+        Rect::new(rect.min.x, visible.max.y, rect.max.x, rect.max.y),
+        Rect::new(rect.min.x, visible.min.y, visible.min.x, visible.max.y),
+        Rect::new(visible.max.x, visible.min.y, rect.max.x, visible.max.y),
+    ]
+    .into_iter()
+    .filter(|part| part.width() > 0.5 && part.height() > 0.5)
+    .collect()
+}
+
+// Highlights nodes whose children don't fit, the clipped parts of nodes and text that is larger
+// than its container, and lists them in a window to select them from.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn show_overflow(
+    windows: Query<&Window>,
+    settings: Res<OverlaySettings>,
+    node_q: Query<OverflowNodeData, (With<Node>, Without<InspectorOverlayMarker>)>,
+    mut previous_resource: ResMut<RestorePreviousResource>,
+    mut style_under_inspection: ResMut<ActiveStyleInspection>,
+    mut gizmos: Gizmos<UiInspectorGizmos>,
+    mut contexts: EguiContexts,
+) {
+    if !settings.overflow {
+        return;
+    }
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let to_gizmo = |p: Vec2| window_to_gizmo(window, p);
+    let mut issues: Vec<(Entity, Option<&Name>, OverflowKind)> = Vec::new();
+
+    for (entity, node, tf, parent, children, clip, text_layout, name) in node_q.iter() {
+        if node.is_empty() {
+            continue;
+        }
+        let rect = node_window_rect(window, node, tf);
+
+        let overflowing_children: Vec<Rect> = children
+            .into_iter()
+            .flatten()
+            .filter_map(|child| node_q.get(*child).ok())
+            .filter(|(_, child_node, ..)| !child_node.is_empty())
+            .map(|(_, child_node, child_tf, ..)| node_window_rect(window, child_node, child_tf))
+            .filter(|child_rect| !contains_rect(rect, *child_rect))
+            .collect();
+        if !overflowing_children.is_empty() {
+            gizmos.rect_2d(to_gizmo(rect.center()), rect.size(), OVERFLOW_COLOR);
+            for child_rect in overflowing_children {
+                for part in clipped_parts(child_rect, rect) {
+                    gizmos.rect_2d(to_gizmo(part.center()), part.size(), OVERFLOW_COLOR);
+                }
+            }
+            issues.push((entity, name, OverflowKind::Children));
+        }
+
+        if let Some(clip) = clip {
+            let clip = Rect {
+                min: clip.clip.min / window.scale_factor(),
+                max: clip.clip.max / window.scale_factor(),
+            };
+            let parts = clipped_parts(rect, clip);
+            if !parts.is_empty() {
+                for part in parts {
+                    hatch(&mut gizmos, part, CLIPPED_COLOR, to_gizmo);
+                }
+                issues.push((entity, name, OverflowKind::Clipped));
+            }
+        }
+
+        if let Some(text_layout) = text_layout {
+            // Text is laid out in physical pixels, like the node itself.
+            let text_size = text_layout.size / window.scale_factor();
+            let text_rect = Rect::from_corners(rect.min, rect.min + text_size);
+            let container = parent.and_then(|parent| node_q.get(parent.get()).ok()).map(
+                |(_, parent_node, parent_tf, ..)| node_window_rect(window, parent_node, parent_tf),
+            );
+            let overflows = !contains_rect(rect, text_rect)
+                || container.is_some_and(|container| !contains_rect(container, rect));
+            if overflows {
+                gizmos.rect_2d(to_gizmo(text_rect.center()), text_rect.size(), TEXT_COLOR);
+                issues.push((entity, name, OverflowKind::Text));
+            }
+        }
+    }
+
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+    egui::Window::new("Overflow").show(ctx, |ui| {
+        if issues.is_empty() {
+            ui.label("Nothing overflows");
+            return;
+        }
+        egui::ScrollArea::vertical()
+            .max_height(300.0)
+            .show(ui, |ui| {
+                for (entity, name, kind) in issues {
+                    let label = match name {
+                        Some(n) => format!("{} ({}): {}", n.as_str(), entity, kind),
+                        None => format!("{}: {}", entity, kind),
+                    };
+                    let is_selected = previous_resource.selected == Some(entity);
+                    if ui.selectable_label(is_selected, label).clicked() {
+                        previous_resource.selected = Some(entity);
+                        style_under_inspection.entity = Some(entity);
+                    }
+                }
+            });
+    });
+}
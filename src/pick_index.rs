@@ -0,0 +1,129 @@
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+
+use crate::{ignore::IgnoredNodes, InspectorOverlayMarker, PickableNodes};
+
+// Side of a grid cell, in physical pixels.
+const CELL_SIZE: f32 = 64.0;
+
+/// Grid of node rects used to find the node under the cursor without walking the whole UI.
+/// It is rebuilt lazily, the first time it is queried after a node moved or resized.
+#[derive(Resource)]
+pub(crate) struct PickIndex {
+    // Nodes overlapping each cell, topmost first.
+    cells: HashMap<IVec2, Vec<(Entity, Rect)>>,
+    // Nodes the inspector is told to ignore.
+    excluded: HashSet<Entity>,
+    dirty: bool,
+}
+
+impl Default for PickIndex {
+    fn default() -> Self {
+        Self {
+            cells: HashMap::default(),
+            excluded: HashSet::default(),
+            dirty: true,
+        }
+    }
+}
+
+impl PickIndex {
+    pub(crate) fn top_node_at(
+        &mut self,
+        window: &Window,
+        node_query: &PickableNodes,
+        cursor_position: Vec2,
+    ) -> Option<Entity> {
+        if self.dirty {
+            self.rebuild(node_query);
+        }
+        // Layout is in physical pixels, the cursor in logical ones.
+        let position = cursor_position * window.scale_factor();
+        let top = self
+            .cells
+            .get(&cell(position))?
+            .iter()
+            .find(|(_, rect)| rect.contains(position))
+            .map(|(entity, _)| *entity);
+        trace!(target: "bevy_ui_inspector::picking", ?position, ?top, "picked node");
+        top
+    }
+
+    fn rebuild(&mut self, node_query: &PickableNodes) {
+        let mut cells: HashMap<IVec2, Vec<(Entity, Rect, u32)>> = HashMap::default();
+        for (entity, global_transform, node) in node_query.iter() {
+            if node.is_empty() || self.excluded.contains(&entity) {
+                continue;
+            }
+            let rect =
+                Rect::from_center_size(global_transform.translation().truncate(), node.size());
+            let (min, max) = (cell(rect.min), cell(rect.max));
+            for y in min.y..=max.y {
+                for x in min.x..=max.x {
+                    cells.entry(IVec2::new(x, y)).or_default().push((
+                        entity,
+                        rect,
+                        node.stack_index(),
+                    ));
+                }
+            }
+        }
+        self.cells = cells
+            .into_iter()
+            .map(|(key, mut nodes)| {
+                nodes.sort_unstable_by_key(|(_, _, stack_index)| std::cmp::Reverse(*stack_index));
+                let nodes = nodes
+                    .into_iter()
+                    .map(|(entity, rect, _)| (entity, rect))
+                    .collect();
+                (key, nodes)
+            })
+            .collect();
+        self.dirty = false;
+        debug!(
+            target: "bevy_ui_inspector::picking",
+            cells = self.cells.len(),
+            "rebuilt pick index"
+        );
+    }
+}
+
+fn cell(position: Vec2) -> IVec2 {
+    (position / CELL_SIZE).floor().as_ivec2()
+}
+
+type MovedNodes = (
+    Or<(
+        Changed<ComputedNode>,
+        Changed<GlobalTransform>,
+        // Restack nodes without touching `ComputedNode`, as bevy writes stack indices
+        // bypassing change detection.
+        Changed<ZIndex>,
+        Changed<GlobalZIndex>,
+        Changed<Parent>,
+        Changed<Children>,
+    )>,
+    With<ComputedNode>,
+    Without<InspectorOverlayMarker>,
+);
+
+// Only flags the index, so moving nodes cost nothing until the next pick. Runs after bevy has
+// stacked the nodes, so the next rebuild sees the new stack indices.
+pub(crate) fn invalidate_pick_index(
+    changed_q: Query<(), MovedNodes>,
+    mut removed: RemovedComponents<ComputedNode>,
+    windows: Query<(), Changed<Window>>,
+    ignored: Res<IgnoredNodes>,
+    mut index: ResMut<PickIndex>,
+) {
+    if ignored.is_changed() {
+        index.excluded = ignored.hidden();
+        index.dirty = true;
+    }
+    let removed = removed.read().count() > 0;
+    if !index.dirty && (removed || !changed_q.is_empty() || !windows.is_empty()) {
+        index.dirty = true;
+    }
+}
@@ -0,0 +1,58 @@
+use bevy::{
+    picking::{backend::PointerHits, pointer::PointerId, PickingPlugin},
+    prelude::*,
+};
+
+use crate::{ignore::IgnoredNodes, InspectorOverlayMarker};
+
+/// Node under the mouse as reported by the `bevy_picking` backends. When enabled, it replaces
+/// the inspector's own hit test.
+#[derive(Resource, Default)]
+pub(crate) struct PointerPick {
+    pub enabled: bool,
+    pub hovered: Option<Entity>,
+    // `PickingPlugin::is_focus_enabled` from before picking paused it.
+    paused_focus: Option<bool>,
+}
+
+impl PointerPick {
+    // Stops `bevy_picking` from hovering and clicking the app's nodes, so their `Pointer`
+    // observers don't see the pick. The backends keep reporting hits, which is all picking needs.
+    pub fn pause_focus(&mut self, picking: &mut PickingPlugin) {
+        if self.paused_focus.is_none() {
+            self.paused_focus = Some(picking.is_focus_enabled);
+            picking.is_focus_enabled = false;
+        }
+    }
+
+    pub fn resume_focus(&mut self, picking: &mut PickingPlugin) {
+        if let Some(was_enabled) = self.paused_focus.take() {
+            picking.is_focus_enabled = was_enabled;
+        }
+    }
+}
+
+// Takes the topmost app node out of this frame's hits: the highest camera order first, then
+// the closest depth.
+pub(crate) fn read_pointer_hits(
+    mut hit_events: EventReader<PointerHits>,
+    node_q: Query<(), (With<Node>, Without<InspectorOverlayMarker>)>,
+    ignored: Res<IgnoredNodes>,
+    mut pointer_pick: ResMut<PointerPick>,
+) {
+    if !pointer_pick.enabled {
+        hit_events.clear();
+        return;
+    }
+    pointer_pick.hovered = hit_events
+        .read()
+        .filter(|hits| hits.pointer == PointerId::Mouse)
+        .flat_map(|hits| {
+            hits.picks
+                .iter()
+                .map(move |(entity, hit)| (hits.order, hit.depth, *entity))
+        })
+        .filter(|(_, _, entity)| node_q.contains(*entity) && !ignored.is_hidden(*entity))
+        .max_by(|a, b| a.0.total_cmp(&b.0).then(b.1.total_cmp(&a.1)))
+        .map(|(_, _, entity)| entity);
+}
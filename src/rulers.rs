@@ -0,0 +1,414 @@
+use bevy::{prelude::*, render::view::RenderLayers};
+use bevy_egui::{egui, EguiContexts};
+
+use crate::{
+    format_px, overlay_node, paint_label, window_to_gizmo, InspectorOverlayMarker,
+    UiInspectorGizmos, UiInspectorSettings,
+};
+
+const RULER_SIZE: f32 = 18.0;
+const GUIDE_COLOR: Color = Color::srgb(0.0, 0.8, 0.9);
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum GuideAxis {
+    // A vertical line, dragged out of the top ruler.
+    Vertical,
+    // A horizontal line, dragged out of the left ruler.
+    Horizontal,
+}
+
+impl GuideAxis {
+    fn along(self, pos: egui::Pos2) -> f32 {
+        match self {
+            GuideAxis::Vertical => pos.x,
+            GuideAxis::Horizontal => pos.y,
+        }
+    }
+    fn across(self, pos: egui::Pos2) -> f32 {
+        match self {
+            GuideAxis::Vertical => pos.y,
+            GuideAxis::Horizontal => pos.x,
+        }
+    }
+}
+
+struct Guide {
+    axis: GuideAxis,
+    // In UI pixels, so guides line up with `Val::Px` values regardless of `UiScale`.
+    position: f32,
+}
+
+/// Pixel rulers along the top and left window edges, and the guides dragged out of them.
+///
+/// The rulers are UI nodes on the overlay camera and the guides are drawn with its gizmos.
+/// Guides are dragged with egui areas laid over them, which also keep picking from going
+/// through the rulers.
+#[derive(Resource, Default)]
+pub struct Rulers {
+    pub enabled: bool,
+    guides: Vec<Guide>,
+    // Guide that is being dragged out of a ruler.
+    dragging: Option<usize>,
+    // The spawned ruler nodes, with what they were laid out for.
+    spawned: Option<(Entity, RulerLayout)>,
+}
+
+// The rulers are spawned again when any of this changes.
+#[derive(Clone, Copy, PartialEq)]
+struct RulerLayout {
+    camera: Entity,
+    // The window size in UI pixels.
+    size: Vec2,
+    scale: f32,
+}
+
+type OverlayCameras<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        Option<&'static RenderLayers>,
+        Has<InspectorOverlayMarker>,
+    ),
+    With<Camera>,
+>;
+
+// The line following the cursor along a ruler.
+#[derive(Component)]
+pub(crate) struct RulerCursor(GuideAxis);
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn draw_rulers(
+    mut contexts: EguiContexts,
+    mut rulers: ResMut<Rulers>,
+    windows: Query<&Window>,
+    ui_scale: Res<UiScale>,
+    settings: Res<UiInspectorSettings>,
+    camera_q: OverlayCameras,
+    mut cursor_q: Query<(&mut Node, &RulerCursor)>,
+    mut gizmos: Gizmos<UiInspectorGizmos>,
+    mut commands: Commands,
+) {
+    let window = windows.get_single().ok();
+    let scale = ui_scale.0;
+    let layout = window
+        .filter(|_| rulers.enabled)
+        .zip(overlay_camera(&settings, &camera_q))
+        .map(|(window, camera)| RulerLayout {
+            camera,
+            size: window.size() / scale,
+            scale,
+        });
+    if rulers.spawned.map(|(_, spawned)| spawned) != layout {
+        if let Some((root, _)) = rulers.spawned.take() {
+            commands.entity(root).despawn_recursive();
+        }
+        rulers.spawned = layout.map(|layout| (spawn_rulers(&mut commands, layout), layout));
+    }
+    let (Some(window), Some(_)) = (window, layout) else {
+        return;
+    };
+
+    let cursor = window.cursor_position().map(|cursor| cursor / scale);
+    for (mut node, RulerCursor(axis)) in &mut cursor_q {
+        let mut new_node = node.clone();
+        match cursor {
+            Some(cursor) => {
+                new_node.display = Display::Flex;
+                match axis {
+                    GuideAxis::Vertical => new_node.left = Val::Px(cursor.x),
+                    GuideAxis::Horizontal => new_node.top = Val::Px(cursor.y - RULER_SIZE / scale),
+                }
+            }
+            None => new_node.display = Display::None,
+        }
+        node.set_if_neq(new_node);
+    }
+
+    for guide in &rulers.guides {
+        let p = guide.position * scale;
+        let (start, end) = match guide.axis {
+            GuideAxis::Vertical => (Vec2::new(p, 0.0), Vec2::new(p, window.height())),
+            GuideAxis::Horizontal => (Vec2::new(0.0, p), Vec2::new(window.width(), p)),
+        };
+        gizmos.line_2d(
+            window_to_gizmo(window, start),
+            window_to_gizmo(window, end),
+            GUIDE_COLOR,
+        );
+    }
+
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+    ruler(ctx, &mut rulers, GuideAxis::Vertical, scale);
+    ruler(ctx, &mut rulers, GuideAxis::Horizontal, scale);
+    guide_handles(ctx, &mut rulers, scale);
+}
+
+// The camera the inspector's overlay is drawn with, its own or the app's on its render layer.
+fn overlay_camera(settings: &UiInspectorSettings, camera_q: &OverlayCameras) -> Option<Entity> {
+    let layer = RenderLayers::layer(settings.render_layer);
+    camera_q
+        .iter()
+        .find(|(_, layers, is_overlay)| {
+            if settings.spawn_camera {
+                *is_overlay
+            } else {
+                layers.is_some_and(|layers| layers.intersects(&layer))
+            }
+        })
+        .map(|(camera, ..)| camera)
+}
+
+// Sizes are in UI pixels, which `UiScale` scales, so the ones that shouldn't grow with it are
+// divided by it.
+fn spawn_rulers(commands: &mut Commands, layout: RulerLayout) -> Entity {
+    let px = |size: f32| Val::Px(size / layout.scale);
+    let thickness = RULER_SIZE / layout.scale;
+    let bar_color = BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.78));
+    let tick_color = Color::srgb_u8(180, 180, 180);
+    let font = TextFont {
+        font_size: 9.0 / layout.scale,
+        ..default()
+    };
+
+    let mut root = commands.spawn((
+        overlay_node(Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(0.0),
+            top: Val::Px(0.0),
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            ..default()
+        }),
+        TargetCamera(layout.camera),
+        GlobalZIndex(i32::MAX),
+        Name::new("Rulers"),
+    ));
+    root.with_children(|builder| {
+        for axis in [GuideAxis::Vertical, GuideAxis::Horizontal] {
+            // The top ruler spans the window, the left one starts below it.
+            let (bar, start, end) = match axis {
+                GuideAxis::Vertical => (
+                    Node {
+                        width: Val::Percent(100.0),
+                        height: Val::Px(thickness),
+                        ..default()
+                    },
+                    0.0,
+                    layout.size.x,
+                ),
+                GuideAxis::Horizontal => (
+                    Node {
+                        top: Val::Px(thickness),
+                        width: Val::Px(thickness),
+                        height: Val::Px(layout.size.y - thickness),
+                        ..default()
+                    },
+                    thickness,
+                    layout.size.y,
+                ),
+            };
+            let bar = Node {
+                position_type: PositionType::Absolute,
+                overflow: Overflow::clip(),
+                ..bar
+            };
+            builder
+                .spawn((overlay_node(bar), bar_color))
+                .with_children(|builder| {
+                    // A tick every 10 UI pixels, a longer one every 50 and a label every 100.
+                    let mut i = (start / 10.0).ceil() as i32;
+                    while i as f32 * 10.0 <= end {
+                        let offset = i as f32 * 10.0 - start;
+                        let length = match i {
+                            i if i % 10 == 0 => RULER_SIZE,
+                            i if i % 5 == 0 => RULER_SIZE * 0.5,
+                            _ => RULER_SIZE * 0.25,
+                        };
+                        let (tick, label) = match axis {
+                            GuideAxis::Vertical => (
+                                Node {
+                                    left: Val::Px(offset),
+                                    bottom: Val::Px(0.0),
+                                    width: px(1.0),
+                                    height: px(length),
+                                    ..default()
+                                },
+                                Node {
+                                    left: Val::Px(offset + 2.0 / layout.scale),
+                                    top: Val::Px(0.0),
+                                    ..default()
+                                },
+                            ),
+                            GuideAxis::Horizontal => (
+                                Node {
+                                    top: Val::Px(offset),
+                                    right: Val::Px(0.0),
+                                    width: px(length),
+                                    height: px(1.0),
+                                    ..default()
+                                },
+                                Node {
+                                    left: px(1.0),
+                                    top: Val::Px(offset + 1.0 / layout.scale),
+                                    ..default()
+                                },
+                            ),
+                        };
+                        builder.spawn((
+                            overlay_node(Node {
+                                position_type: PositionType::Absolute,
+                                ..tick
+                            }),
+                            BackgroundColor(tick_color),
+                        ));
+                        if i % 10 == 0 {
+                            builder.spawn((
+                                overlay_node(Node {
+                                    position_type: PositionType::Absolute,
+                                    ..label
+                                }),
+                                Text::new((i * 10).to_string()),
+                                font.clone(),
+                                TextColor(tick_color),
+                            ));
+                        }
+                        i += 1;
+                    }
+
+                    let cursor = match axis {
+                        GuideAxis::Vertical => Node {
+                            width: px(1.0),
+                            height: Val::Percent(100.0),
+                            ..default()
+                        },
+                        GuideAxis::Horizontal => Node {
+                            width: Val::Percent(100.0),
+                            height: px(1.0),
+                            ..default()
+                        },
+                    };
+                    builder.spawn((
+                        overlay_node(Node {
+                            position_type: PositionType::Absolute,
+                            display: Display::None,
+                            ..cursor
+                        }),
+                        BackgroundColor(Color::srgb(1.0, 0.0, 0.0)),
+                        RulerCursor(axis),
+                    ));
+                });
+        }
+    });
+    root.id()
+}
+
+// Reads dragging guides out of the ruler, which is drawn by `spawn_rulers`.
+fn ruler(ctx: &egui::Context, rulers: &mut Rulers, axis: GuideAxis, scale: f32) {
+    let screen = ctx.screen_rect();
+    let (pos, size) = match axis {
+        GuideAxis::Vertical => (egui::pos2(0.0, 0.0), egui::vec2(screen.width(), RULER_SIZE)),
+        GuideAxis::Horizontal => (
+            egui::pos2(0.0, RULER_SIZE),
+            egui::vec2(RULER_SIZE, screen.height() - RULER_SIZE),
+        ),
+    };
+
+    egui::Area::new(egui::Id::new(("ui_inspector_ruler", axis)))
+        .order(egui::Order::Background)
+        .fixed_pos(pos)
+        .show(ctx, |ui| {
+            let (_, response) = ui.allocate_exact_size(size, egui::Sense::drag());
+
+            if response.drag_started() {
+                if let Some(pointer) = response.interact_pointer_pos() {
+                    rulers.guides.push(Guide {
+                        axis,
+                        position: axis.along(pointer) / scale,
+                    });
+                    rulers.dragging = Some(rulers.guides.len() - 1);
+                }
+            }
+            if let Some(index) = rulers.dragging.filter(|_| response.dragged()) {
+                if let Some(pointer) = response.interact_pointer_pos() {
+                    drag_guide(ctx, rulers, index, pointer, scale);
+                }
+            }
+            if response.drag_stopped() {
+                if let Some(index) = rulers.dragging.take() {
+                    drop_guide(ctx, rulers, index);
+                }
+            }
+        });
+}
+
+fn guide_handles(ctx: &egui::Context, rulers: &mut Rulers, scale: f32) {
+    let screen = ctx.screen_rect();
+    let mut dropped = None;
+    for index in 0..rulers.guides.len() {
+        if rulers.dragging == Some(index) {
+            continue;
+        }
+        let guide = &rulers.guides[index];
+        let p = guide.position * scale;
+        let (pos, size, cursor_icon) = match guide.axis {
+            GuideAxis::Vertical => (
+                egui::pos2(p - 3.0, RULER_SIZE),
+                egui::vec2(6.0, screen.height() - RULER_SIZE),
+                egui::CursorIcon::ResizeHorizontal,
+            ),
+            GuideAxis::Horizontal => (
+                egui::pos2(RULER_SIZE, p - 3.0),
+                egui::vec2(screen.width() - RULER_SIZE, 6.0),
+                egui::CursorIcon::ResizeVertical,
+            ),
+        };
+        egui::Area::new(egui::Id::new(("ui_inspector_guide", index)))
+            .order(egui::Order::Background)
+            .fixed_pos(pos)
+            .show(ctx, |ui| {
+                let (_, response) = ui.allocate_exact_size(size, egui::Sense::drag());
+                let response = response.on_hover_cursor(cursor_icon);
+                if response.dragged() {
+                    if let Some(pointer) = response.interact_pointer_pos() {
+                        drag_guide(ctx, rulers, index, pointer, scale);
+                    }
+                }
+                if response.drag_stopped() {
+                    dropped = Some(index);
+                }
+            });
+    }
+    if let Some(index) = dropped {
+        drop_guide(ctx, rulers, index);
+    }
+}
+
+fn drag_guide(
+    ctx: &egui::Context,
+    rulers: &mut Rulers,
+    index: usize,
+    pointer: egui::Pos2,
+    scale: f32,
+) {
+    let guide = &mut rulers.guides[index];
+    guide.position = (guide.axis.along(pointer) / scale).max(0.0);
+    paint_label(
+        ctx,
+        "ui_inspector_guides",
+        pointer + egui::vec2(24.0, 12.0),
+        format_px(guide.position),
+        egui::Color32::from_rgb(0, 150, 170),
+    );
+}
+
+// Guides that are dropped back onto their ruler are removed.
+fn drop_guide(ctx: &egui::Context, rulers: &mut Rulers, index: usize) {
+    let pointer = ctx.input(|i| i.pointer.interact_pos());
+    let guide = &rulers.guides[index];
+    if pointer.is_some_and(|pointer| guide.axis.across(pointer) < RULER_SIZE) {
+        rulers.guides.remove(index);
+    }
+}
@@ -0,0 +1,146 @@
+use bevy::{
+    ecs::{archetype::Archetypes, component::Components, entity::Entities},
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+
+use crate::tree_model::TreeModel;
+
+/// State of the search box above the node hierarchy.
+#[derive(Resource, Default)]
+pub(crate) struct TreeSearch {
+    pub query: String,
+    // Matching nodes in the order their rows appear in the tree.
+    pub matches: Vec<Entity>,
+    // Index in `matches` of the node Enter last jumped to.
+    pub current: Option<usize>,
+    // Nodes with a match somewhere below them.
+    pub ancestors: HashSet<Entity>,
+    // Set for the frame the results changed, so the tree is only expanded or collapsed then.
+    pub results_changed: bool,
+}
+
+impl TreeSearch {
+    pub fn is_active(&self) -> bool {
+        !self.query.trim().is_empty()
+    }
+
+    pub fn is_match(&self, entity: Entity) -> bool {
+        self.matches.contains(&entity)
+    }
+
+    pub fn is_current(&self, entity: Entity) -> bool {
+        self.current.map(|current| self.matches[current]) == Some(entity)
+    }
+
+    // Moves to the next or previous match and returns it.
+    pub fn step(&mut self, backwards: bool) -> Option<Entity> {
+        let len = self.matches.len();
+        if len == 0 {
+            return None;
+        }
+        let next = match (self.current, backwards) {
+            (None, false) => 0,
+            (None, true) => len - 1,
+            (Some(current), false) => (current + 1) % len,
+            (Some(current), true) => (current + len - 1) % len,
+        };
+        self.current = Some(next);
+        Some(self.matches[next])
+    }
+}
+
+type SearchNodeData = (Option<&'static Name>, Option<&'static Text>);
+
+type SearchedChanged = (Or<(Changed<Name>, Changed<Text>)>, With<Node>);
+
+// Matches the query against every node in the tree, in the order the rows are drawn. The
+// results are kept until the query, the tree or a name or text changes.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn update_search_matches(
+    mut search: ResMut<TreeSearch>,
+    mut searched: Local<(String, u64)>,
+    changed_q: Query<(), SearchedChanged>,
+    tree_model: Res<TreeModel>,
+    node_q: Query<SearchNodeData, With<Node>>,
+    entities: &Entities,
+    archetypes: &Archetypes,
+    components: &Components,
+) {
+    if !search.is_active() {
+        if !search.matches.is_empty() || !search.ancestors.is_empty() {
+            search.matches.clear();
+            search.ancestors.clear();
+            search.current = None;
+            search.results_changed = true;
+        } else {
+            search.results_changed = false;
+        }
+        searched.0.clear();
+        return;
+    }
+    let query = search.query.trim().to_lowercase();
+    if query == searched.0 && tree_model.revision() == searched.1 && changed_q.is_empty() {
+        search.results_changed = false;
+        return;
+    }
+    *searched = (query.clone(), tree_model.revision());
+
+    // Nodes of the same archetype have the same components, so their names are checked once.
+    let mut archetype_matches = HashMap::new();
+    let mut component_names_match = |entity: Entity| {
+        let Some(archetype) = entities
+            .get(entity)
+            .and_then(|location| archetypes.get(location.archetype_id))
+        else {
+            return false;
+        };
+        *archetype_matches.entry(archetype.id()).or_insert_with(|| {
+            archetype.components().any(|id| {
+                components
+                    .get_info(id)
+                    .is_some_and(|info| short_name(info.name()).contains(&query))
+            })
+        })
+    };
+
+    let mut matches = Vec::new();
+    let mut ancestors = HashSet::new();
+    // The nodes above the one being visited.
+    let mut path: Vec<Entity> = Vec::new();
+    for (entity, depth) in tree_model.nodes() {
+        path.truncate(depth);
+        let Ok((name, text)) = node_q.get(entity) else {
+            path.push(entity);
+            continue;
+        };
+        let is_match = name.is_some_and(|name| name.to_lowercase().contains(&query))
+            || entity.to_string().to_lowercase().contains(&query)
+            || text.is_some_and(|text| text.to_lowercase().contains(&query))
+            || component_names_match(entity);
+        if is_match {
+            matches.push(entity);
+            ancestors.extend(path.iter().copied());
+        }
+        path.push(entity);
+    }
+
+    search.results_changed = matches != search.matches;
+    if search.results_changed {
+        // Keep stepping from the same node if it still matches.
+        let current = search.current.map(|current| search.matches[current]);
+        search.current = current.and_then(|current| matches.iter().position(|m| *m == current));
+    }
+    search.matches = matches;
+    search.ancestors = ancestors;
+}
+
+// `bevy_ui::widget::button::Button` -> `button`, lowercased for matching.
+fn short_name(type_name: &str) -> String {
+    let without_generics = type_name.split('<').next().unwrap_or(type_name);
+    without_generics
+        .rsplit("::")
+        .next()
+        .unwrap_or(without_generics)
+        .to_lowercase()
+}
@@ -0,0 +1,482 @@
+use bevy::{
+    prelude::*, render::camera::NormalizedRenderTarget, ui::DefaultUiCamera, utils::HashSet,
+    window::PrimaryWindow,
+};
+use bevy_egui::egui;
+
+use crate::{
+    hide::HiddenNodes,
+    hierarchy_edit::{self, TreeEdit, TreeEdits},
+    ignore::IgnoredNodes,
+    search::TreeSearch,
+    tree_row::{self, RowHighlight, TreeNodes},
+    ActiveStyleInspection, AllUiRoots, RestorePreviousResource,
+};
+
+const INDENT: f32 = 12.0;
+
+enum RowKind {
+    // Header of the roots drawn by a camera, `None` for roots no camera draws.
+    Group {
+        camera: Option<Entity>,
+        title: String,
+    },
+    Node(Entity),
+}
+
+struct TreeRow {
+    kind: RowKind,
+    depth: usize,
+    // Index of the first row after this row's subtree.
+    end: usize,
+}
+
+impl TreeRow {
+    fn has_children(&self, index: usize) -> bool {
+        self.end > index + 1
+    }
+
+    fn entity(&self) -> Option<Entity> {
+        match self.kind {
+            RowKind::Node(entity) => Some(entity),
+            RowKind::Group { .. } => None,
+        }
+    }
+}
+
+/// The hierarchy flattened in the order it is drawn, with the roots grouped under the camera
+/// that draws them. It only changes with the structure of the UI, labels are read from the
+/// nodes when their row is drawn.
+#[derive(Resource)]
+pub(crate) struct TreeModel {
+    rows: Vec<TreeRow>,
+    expanded: HashSet<Entity>,
+    // Groups start open, so the roots of a new camera are listed right away.
+    collapsed_groups: HashSet<Option<Entity>>,
+    // Indices of the rows that aren't inside a collapsed node.
+    visible: Vec<usize>,
+    rows_dirty: bool,
+    visible_dirty: bool,
+    // Row to bring into view the next time the tree is drawn.
+    scroll_to: Option<Entity>,
+    // Roots with their camera, in the order the rows were built with.
+    root_order: Vec<(Option<Entity>, Entity)>,
+    // Bumped whenever the rows are rebuilt.
+    revision: u64,
+}
+
+impl Default for TreeModel {
+    fn default() -> Self {
+        Self {
+            rows: Vec::new(),
+            expanded: HashSet::new(),
+            collapsed_groups: HashSet::new(),
+            visible: Vec::new(),
+            rows_dirty: true,
+            visible_dirty: true,
+            scroll_to: None,
+            root_order: Vec::new(),
+            revision: 0,
+        }
+    }
+}
+
+impl TreeModel {
+    pub fn is_expanded(&self, entity: Entity) -> bool {
+        self.expanded.contains(&entity)
+    }
+
+    pub fn set_expanded(&mut self, entity: Entity, expanded: bool) {
+        let changed = if expanded {
+            self.expanded.insert(entity)
+        } else {
+            self.expanded.remove(&entity)
+        };
+        self.visible_dirty |= changed;
+    }
+
+    fn set_group_expanded(&mut self, camera: Option<Entity>, expanded: bool) {
+        let changed = if expanded {
+            self.collapsed_groups.remove(&camera)
+        } else {
+            self.collapsed_groups.insert(camera)
+        };
+        self.visible_dirty |= changed;
+    }
+
+    pub fn expand_all(&mut self) {
+        self.expanded = self.rows.iter().filter_map(TreeRow::entity).collect();
+        self.collapsed_groups.clear();
+        self.visible_dirty = true;
+    }
+
+    // Groups stay open, so every root remains listed.
+    pub fn collapse_all(&mut self) {
+        self.expanded.clear();
+        self.visible_dirty = true;
+    }
+
+    // Opens exactly these nodes, closing everything else.
+    pub fn expand_only(&mut self, entities: impl IntoIterator<Item = Entity>) {
+        self.expanded = entities.into_iter().collect();
+        self.collapsed_groups.clear();
+        self.visible_dirty = true;
+    }
+
+    pub fn scroll_to(&mut self, entity: Entity) {
+        self.scroll_to = Some(entity);
+    }
+
+    pub fn expand(&mut self, entities: impl IntoIterator<Item = Entity>) {
+        for entity in entities {
+            self.set_expanded(entity, true);
+        }
+        if !self.collapsed_groups.is_empty() {
+            self.collapsed_groups.clear();
+            self.visible_dirty = true;
+        }
+    }
+
+    fn rebuild(
+        &mut self,
+        groups: Vec<RootGroup>,
+        node_q: &Query<Option<&Children>, With<Node>>,
+        ignored: &IgnoredNodes,
+    ) {
+        self.rows.clear();
+        for group in groups {
+            let index = self.rows.len();
+            self.rows.push(TreeRow {
+                kind: RowKind::Group {
+                    camera: group.camera,
+                    title: group.title,
+                },
+                depth: 0,
+                end: index + 1,
+            });
+            for root in group.roots {
+                self.push_subtree(root, 1, node_q, ignored);
+            }
+            self.rows[index].end = self.rows.len();
+        }
+        self.rows_dirty = false;
+        self.visible_dirty = true;
+        self.revision += 1;
+    }
+
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    // Every node in the tree with its depth below its group, in the order it is drawn.
+    pub fn nodes(&self) -> impl Iterator<Item = (Entity, usize)> + '_ {
+        self.rows
+            .iter()
+            .filter_map(|row| row.entity().map(|entity| (entity, row.depth - 1)))
+    }
+
+    fn push_subtree(
+        &mut self,
+        entity: Entity,
+        depth: usize,
+        node_q: &Query<Option<&Children>, With<Node>>,
+        ignored: &IgnoredNodes,
+    ) {
+        let Ok(children) = node_q.get(entity) else {
+            return;
+        };
+        if ignored.is_hidden(entity) {
+            return;
+        }
+        let index = self.rows.len();
+        self.rows.push(TreeRow {
+            kind: RowKind::Node(entity),
+            depth,
+            end: index + 1,
+        });
+        for child in children.into_iter().flatten() {
+            self.push_subtree(*child, depth + 1, node_q, ignored);
+        }
+        self.rows[index].end = self.rows.len();
+    }
+
+    fn visible_rows(&mut self) -> &[usize] {
+        if self.visible_dirty {
+            self.visible.clear();
+            let mut index = 0;
+            while let Some(row) = self.rows.get(index) {
+                self.visible.push(index);
+                let expanded = match &row.kind {
+                    RowKind::Group { camera, .. } => !self.collapsed_groups.contains(camera),
+                    RowKind::Node(entity) => self.expanded.contains(entity),
+                };
+                index = if expanded { index + 1 } else { row.end };
+            }
+            self.visible_dirty = false;
+        }
+        &self.visible
+    }
+}
+
+struct RootGroup {
+    camera: Option<Entity>,
+    title: String,
+    roots: Vec<Entity>,
+}
+
+type StructureChanged = (
+    Or<(Changed<Children>, Changed<Parent>, Added<Node>)>,
+    With<Node>,
+);
+
+type CameraChanged = (Or<(Changed<Camera>, Changed<Name>)>, With<Camera>);
+
+// Rebuilds the rows only when nodes are added, removed or moved, roots are restacked or a
+// camera changes.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn update_tree_model(
+    changed_q: Query<(), StructureChanged>,
+    changed_camera_q: Query<(), CameraChanged>,
+    mut removed_nodes: RemovedComponents<Node>,
+    mut removed_children: RemovedComponents<Children>,
+    mut removed_parents: RemovedComponents<Parent>,
+    mut removed_cameras: RemovedComponents<Camera>,
+    ignored: Res<IgnoredNodes>,
+    root_q: AllUiRoots<(Entity, Option<&TargetCamera>, &ComputedNode)>,
+    node_q: Query<Option<&Children>, With<Node>>,
+    camera_q: Query<(&Camera, Option<&Name>)>,
+    window_q: Query<&Window>,
+    primary_window_q: Query<Entity, With<PrimaryWindow>>,
+    default_ui_camera: DefaultUiCamera,
+    mut model: ResMut<TreeModel>,
+) {
+    // Every reader is drained, so old removals don't cause another rebuild next frame.
+    let removed = removed_nodes.read().count()
+        + removed_children.read().count()
+        + removed_parents.read().count()
+        + removed_cameras.read().count()
+        > 0;
+    // Back to front, the order the roots are stacked in. Stack indices are only known after
+    // layout and change without change detection, so the order is compared every frame.
+    let mut roots: Vec<_> = root_q
+        .iter()
+        .map(|(root, target_camera, node)| {
+            let camera = target_camera
+                .map(TargetCamera::entity)
+                .or_else(|| default_ui_camera.get());
+            (node.stack_index(), camera, root)
+        })
+        .collect();
+    roots.sort_by_key(|(stack_index, ..)| *stack_index);
+    let root_order: Vec<_> = roots
+        .iter()
+        .map(|(_, camera, root)| (*camera, *root))
+        .collect();
+    if !model.rows_dirty
+        && !removed
+        && !ignored.is_changed()
+        && changed_q.is_empty()
+        && changed_camera_q.is_empty()
+        && model.root_order == root_order
+    {
+        return;
+    }
+    model.root_order = root_order;
+
+    let mut groups: Vec<RootGroup> = Vec::new();
+    for (_, camera, root) in roots {
+        match groups.iter_mut().find(|group| group.camera == camera) {
+            Some(group) => group.roots.push(root),
+            None => groups.push(RootGroup {
+                camera,
+                title: group_title(camera, &camera_q, &window_q, &primary_window_q),
+                roots: vec![root],
+            }),
+        }
+    }
+    // Cameras in the order they render, roots without one last.
+    groups.sort_by_key(|group| {
+        group
+            .camera
+            .and_then(|camera| camera_q.get(camera).ok())
+            .map_or(isize::MAX, |(camera, _)| camera.order)
+    });
+    model.rebuild(groups, &node_q, &ignored);
+}
+
+// "Window title › Camera name (entity)"
+fn group_title(
+    camera: Option<Entity>,
+    camera_q: &Query<(&Camera, Option<&Name>)>,
+    window_q: &Query<&Window>,
+    primary_window_q: &Query<Entity, With<PrimaryWindow>>,
+) -> String {
+    let Some((entity, (camera, name))) =
+        camera.and_then(|entity| camera_q.get(entity).ok().map(|camera| (entity, camera)))
+    else {
+        return "No camera".to_string();
+    };
+    let target = match camera.target.normalize(primary_window_q.get_single().ok()) {
+        Some(NormalizedRenderTarget::Window(window)) => window_q
+            .get(window.entity())
+            .map_or_else(|_| "Window".to_string(), |window| window.title.clone()),
+        Some(NormalizedRenderTarget::Image(_)) => "Image".to_string(),
+        Some(NormalizedRenderTarget::TextureView(_)) => "Texture view".to_string(),
+        None => "No target".to_string(),
+    };
+    let name = name.map_or("Camera", Name::as_str);
+    format!("{target} › {name} ({entity})")
+}
+
+// Draws the rows in view, returns whether one of them is hovered.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn show_tree(
+    ui: &mut egui::Ui,
+    model: &mut TreeModel,
+    ui_q: &TreeNodes,
+    previous_resource: &mut RestorePreviousResource,
+    style_under_inspection: &mut ActiveStyleInspection,
+    search: &TreeSearch,
+    tree_edits: &mut TreeEdits,
+    hidden_nodes: &HiddenNodes,
+) -> bool {
+    let mut something_hovered = false;
+    let row_height = ui.spacing().interact_size.y;
+    let visible_rows = model.visible_rows().to_vec();
+    let mut scroll_area = egui::ScrollArea::vertical()
+        .id_salt("ui_inspector_tree")
+        .auto_shrink([false, false]);
+    if let Some(position) = model.scroll_to.take().and_then(|entity| {
+        visible_rows
+            .iter()
+            .position(|index| model.rows[*index].entity() == Some(entity))
+    }) {
+        // Rows already in view are left where they are, others are scrolled to the middle.
+        let spacing = row_height + ui.spacing().item_spacing.y;
+        let viewport = ui.available_height();
+        let row_top = position as f32 * spacing;
+        let offset = egui::scroll_area::State::load(
+            ui.ctx(),
+            ui.make_persistent_id(egui::Id::new("ui_inspector_tree")),
+        )
+        .map_or(0.0, |state| state.offset.y);
+        if row_top < offset || row_top + spacing > offset + viewport {
+            scroll_area =
+                scroll_area.vertical_scroll_offset((row_top - (viewport - spacing) / 2.0).max(0.0));
+        }
+    }
+    scroll_area.show_rows(ui, row_height, visible_rows.len(), |ui, range| {
+        for index in visible_rows[range].iter().copied() {
+            let row = &model.rows[index];
+            let (depth, has_children) = (row.depth, row.has_children(index));
+            let width = ui.available_width();
+            let entity = match &row.kind {
+                RowKind::Group { camera, title } => {
+                    let (camera, title) = (*camera, title.clone());
+                    ui.allocate_ui_with_layout(
+                        egui::vec2(width, row_height),
+                        egui::Layout::left_to_right(egui::Align::Center),
+                        |ui| {
+                            let expanded = !model.collapsed_groups.contains(&camera);
+                            if expand_toggle(ui, expanded, has_children) {
+                                model.set_group_expanded(camera, !expanded);
+                            }
+                            ui.add(
+                                egui::Label::new(egui::RichText::new(title).strong())
+                                    .selectable(false)
+                                    .truncate(),
+                            );
+                        },
+                    );
+                    continue;
+                }
+                RowKind::Node(entity) => *entity,
+            };
+            let Ok(item) = ui_q.get(entity) else {
+                continue;
+            };
+            let highlight = if previous_resource.selected == Some(entity) {
+                RowHighlight::Selected
+            } else if search.is_current(entity) {
+                RowHighlight::CurrentSearchMatch
+            } else if search.is_match(entity) {
+                RowHighlight::SearchMatch
+            } else {
+                RowHighlight::None
+            };
+            let mut label = tree_row::row_label(ui, &item, highlight);
+            if depth == 1 {
+                tree_row::append_root_details(ui, &mut label, &item);
+            }
+            let name = item.2.map(|name| name.to_string()).unwrap_or_default();
+
+            ui.allocate_ui_with_layout(
+                egui::vec2(width, row_height),
+                egui::Layout::left_to_right(egui::Align::Center),
+                |ui| {
+                    let hidden = hidden_nodes.is_hidden(entity);
+                    let eye = egui::RichText::new("👁");
+                    let eye = if hidden { eye.weak() } else { eye };
+                    let eye = ui
+                        .add(
+                            egui::Label::new(eye)
+                                .selectable(false)
+                                .sense(egui::Sense::click()),
+                        )
+                        .on_hover_text(if hidden { "show" } else { "hide" });
+                    if eye.clicked() {
+                        tree_edits.pending.push(TreeEdit::ToggleHidden(entity));
+                    }
+                    ui.add_space(depth as f32 * INDENT);
+                    let expanded = model.is_expanded(entity);
+                    if expand_toggle(ui, expanded, has_children) {
+                        model.set_expanded(entity, !expanded);
+                    }
+                    if tree_edits
+                        .renaming
+                        .as_ref()
+                        .is_some_and(|(renaming, _)| *renaming == entity)
+                    {
+                        hierarchy_edit::rename_row(ui, tree_edits);
+                        return;
+                    }
+
+                    let response = ui.add(
+                        egui::Label::new(label)
+                            .selectable(false)
+                            .truncate()
+                            .sense(egui::Sense::click()),
+                    );
+                    if response.clicked() {
+                        previous_resource.selected = Some(entity);
+                        style_under_inspection.entity = Some(entity);
+                    }
+                    if response.double_clicked() {
+                        tree_edits.renaming = Some((entity, name));
+                    }
+                    if response.hovered() {
+                        previous_resource.hovered = Some(entity);
+                        something_hovered = true;
+                    }
+                    hierarchy_edit::row_context_menu(&response, entity, tree_edits);
+                    hierarchy_edit::drag_and_drop_row(ui, entity, response.rect, tree_edits);
+                },
+            );
+        }
+    });
+    something_hovered
+}
+
+// The arrow in front of a row. It still takes up its space when there is nothing to expand, so
+// siblings line up.
+fn expand_toggle(ui: &mut egui::Ui, expanded: bool, shown: bool) -> bool {
+    let toggle = egui::Label::new(if expanded { "⏷" } else { "⏵" })
+        .selectable(false)
+        .sense(egui::Sense::click());
+    if shown {
+        ui.add(toggle).clicked()
+    } else {
+        ui.add_visible(false, toggle);
+        false
+    }
+}
@@ -0,0 +1,168 @@
+use bevy::{ecs::query::QueryItem, prelude::*};
+use bevy_egui::egui::{self, text::LayoutJob, TextFormat};
+
+use crate::format_px;
+
+const PREVIEW_LENGTH: usize = 24;
+
+pub(crate) type TreeNodeData = (
+    Entity,
+    Option<&'static Children>,
+    Option<&'static Name>,
+    &'static Node,
+    Option<&'static InheritedVisibility>,
+    Option<&'static Text>,
+    Option<&'static ScrollPosition>,
+    Has<Button>,
+    Has<ImageNode>,
+    Has<Interaction>,
+    &'static ComputedNode,
+    Option<&'static GlobalZIndex>,
+);
+
+pub(crate) type TreeNodes<'w, 's> = Query<'w, 's, TreeNodeData, With<Node>>;
+
+pub(crate) enum RowHighlight {
+    None,
+    Selected,
+    SearchMatch,
+    CurrentSearchMatch,
+}
+
+// A tree row: the name or a fallback describing the node, badges for the components that
+// matter most when reading a UI, and a preview of its text. Hidden nodes are dimmed.
+pub(crate) fn row_label(
+    ui: &egui::Ui,
+    item: &QueryItem<TreeNodeData>,
+    highlight: RowHighlight,
+) -> LayoutJob {
+    let (entity, _, _, node, visibility, text, scroll, is_button, is_image, has_interaction, _, _) =
+        *item;
+    let hidden =
+        node.display == Display::None || visibility.is_some_and(|visibility| !visibility.get());
+    let text_color = if hidden {
+        ui.visuals().weak_text_color()
+    } else {
+        ui.visuals().text_color()
+    };
+    let (color, background) = match highlight {
+        RowHighlight::None => (text_color, egui::Color32::TRANSPARENT),
+        RowHighlight::Selected => (
+            egui::Color32::from_rgb(255, 255, 255),
+            egui::Color32::from_rgb(100, 149, 237),
+        ),
+        RowHighlight::SearchMatch => (egui::Color32::BLACK, egui::Color32::from_rgb(255, 221, 87)),
+        RowHighlight::CurrentSearchMatch => {
+            (egui::Color32::BLACK, egui::Color32::from_rgb(255, 165, 0))
+        }
+    };
+
+    let label = format!("{} ({entity})", node_title(item));
+
+    let mut job = LayoutJob::default();
+    job.append(
+        &label,
+        0.0,
+        TextFormat {
+            color,
+            background,
+            ..default()
+        },
+    );
+
+    let scrolls = scroll.is_some_and(|scroll| scroll.offset_x != 0.0 || scroll.offset_y != 0.0)
+        || node.overflow.x == OverflowAxis::Scroll
+        || node.overflow.y == OverflowAxis::Scroll;
+    for (badge, shown) in [
+        ("Text", text.is_some()),
+        ("Button", is_button),
+        ("Image", is_image),
+        ("Interaction", has_interaction),
+        ("Scroll", scrolls),
+        ("hidden", hidden),
+    ] {
+        if shown {
+            job.append(
+                badge,
+                4.0,
+                TextFormat {
+                    font_id: egui::FontId::proportional(9.0),
+                    color: egui::Color32::WHITE,
+                    background: egui::Color32::from_gray(90),
+                    valign: egui::Align::Center,
+                    ..default()
+                },
+            );
+        }
+    }
+
+    if let Some(text) = text.filter(|text| !text.trim().is_empty()) {
+        let text = text.trim().replace('\n', " ");
+        let preview = if text.chars().count() > PREVIEW_LENGTH {
+            format!(
+                "\"{}…\"",
+                text.chars().take(PREVIEW_LENGTH).collect::<String>()
+            )
+        } else {
+            format!("\"{text}\"")
+        };
+        job.append(
+            &preview,
+            4.0,
+            TextFormat {
+                color: ui.visuals().weak_text_color(),
+                italics: true,
+                ..default()
+            },
+        );
+    }
+    job
+}
+
+// Size of a root in logical pixels, and the global z index it is stacked with.
+pub(crate) fn append_root_details(
+    ui: &egui::Ui,
+    job: &mut LayoutJob,
+    item: &QueryItem<TreeNodeData>,
+) {
+    let (.., computed, global_z_index) = *item;
+    let size = computed.size() * computed.inverse_scale_factor();
+    let mut details = format!("{}×{}", format_px(size.x), format_px(size.y));
+    if let Some(GlobalZIndex(z)) = global_z_index {
+        details.push_str(&format!(" z {z}"));
+    }
+    job.append(
+        &details,
+        4.0,
+        TextFormat {
+            color: ui.visuals().weak_text_color(),
+            ..default()
+        },
+    );
+}
+
+// The node's name, or what kind of node it is when it has none.
+pub(crate) fn node_title(item: &QueryItem<TreeNodeData>) -> String {
+    let (_, _, name, node, _, text, _, is_button, is_image, _, _, _) = *item;
+    if let Some(name) = name {
+        return name.as_str().to_string();
+    }
+    let kind = if is_button {
+        "Button"
+    } else if text.is_some() {
+        "Text"
+    } else if is_image {
+        "Image"
+    } else {
+        match node.display {
+            Display::Flex => match node.flex_direction {
+                FlexDirection::Row | FlexDirection::RowReverse => "Row",
+                FlexDirection::Column | FlexDirection::ColumnReverse => "Column",
+            },
+            Display::Grid => "Grid",
+            Display::Block => "Block",
+            Display::None => "Node",
+        }
+    };
+    kind.to_string()
+}
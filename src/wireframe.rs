@@ -0,0 +1,60 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use crate::{node_window_rect, window_to_gizmo, OverlaySettings, UiInspectorGizmos, UiRoots};
+
+// Outlines every node of the app, colored by its depth in the tree.
+pub(crate) fn draw_wireframe(
+    windows: Query<&Window>,
+    settings: Res<OverlaySettings>,
+    root_q: UiRoots<Entity>,
+    node_q: Query<(
+        &ComputedNode,
+        &GlobalTransform,
+        Option<&Children>,
+        Option<&Name>,
+    )>,
+    mut gizmos: Gizmos<UiInspectorGizmos>,
+    mut contexts: EguiContexts,
+) {
+    if !settings.wireframe {
+        return;
+    }
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let painter = contexts
+        .try_ctx_mut()
+        .filter(|_| settings.wireframe_names)
+        .map(|ctx| {
+            ctx.layer_painter(egui::LayerId::new(
+                egui::Order::Background,
+                egui::Id::new("ui_inspector_wireframe"),
+            ))
+        });
+
+    let mut stack: Vec<(Entity, usize)> = root_q.iter().map(|root| (root, 0)).collect();
+    while let Some((entity, depth)) = stack.pop() {
+        let Ok((node, tf, children, name)) = node_q.get(entity) else {
+            continue;
+        };
+        let color = Color::hsl((depth as f32 * 47.0) % 360.0, 0.9, 0.6);
+        let rect = node_window_rect(window, node, tf);
+        if !node.is_empty() {
+            gizmos.rect_2d(window_to_gizmo(window, rect.center()), rect.size(), color);
+            if let (Some(painter), Some(name)) = (&painter, name) {
+                let [r, g, b, _] = color.to_srgba().to_u8_array();
+                painter.text(
+                    egui::pos2(rect.min.x + 2.0, rect.min.y + 1.0),
+                    egui::Align2::LEFT_TOP,
+                    name.as_str(),
+                    egui::FontId::monospace(9.0),
+                    egui::Color32::from_rgb(r, g, b),
+                );
+            }
+        }
+        if let Some(children) = children {
+            stack.extend(children.iter().map(|child| (*child, depth + 1)));
+        }
+    }
+}